@@ -1,256 +1,533 @@
-use nom::{
-    bytes::complete::{tag, take_till},
-    character::complete::{multispace0, multispace1},
-    multi::many0,
-    AsChar, IResult,
-};
-
-use crate::{
-    FArgument, FArrayType, FAttribute, FBroadcast, FEnumerationType, FEnumerator, FField, FMapType,
-    FMethod, FStructType, FUnionType,
-};
+//! Franca deployment models (`.fdepl`), see [FDeploy.xtext](https://github.com/franca/franca/blob/108a9358c466bb14499d263ed6862c6b649d8c70/plugins/org.franca.deploymodel.dsl/src/org/franca/deploymodel/dsl/FDeploy.xtext).
+//!
+//! Deployment elements reference their specification and their Franca IDL
+//! targets by possibly qualified name, see [`FdModel::link`] for resolving the targets.
+
+pub mod link;
+pub mod parser;
+mod resolve;
+pub mod validate;
+pub mod value;
+
+pub use parser::parse_fdepl;
+
+use std::fmt;
+
+use crate::Span;
 
 /// Deployment model root
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdModel {
-    name: String,
-    imports: Vec<Import>,
-    specifications: Vec<FdSpecification>,
-    deployments: Vec<FdRootElement>,
-}
-
-pub fn fd_model(s: &str) -> IResult<&str, FdModel> {
-    let (s, _) = multispace0(s)?;
-    let (s, _) = tag("package")(s)?;
-    let (s, _) = multispace1(s)?;
-    let (s, name) = fqn(s)?;
-    let (s, imports) = many0(import)(s)?;
-    let (s, specifications) = many0(fd_specification)(s)?;
-    let (s, deployments) = many0(fd_root_element)(s)?;
-
-    Ok((
-        s,
-        FdModel {
-            name: name.to_string(),
-            imports,
-            specifications,
-            deployments,
-        },
-    ))
+    /// Package declaration
+    pub name: String,
+    pub imports: Vec<Import>,
+    pub specifications: Vec<FdSpecification>,
+    pub deployments: Vec<FdRootElement>,
+    pub span: Span,
 }
 
+/// e.g. `import "CommonAPI-SomeIP_deployment_spec.fdepl"` or `import org.example.spec`
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Import {
     ImportUri(String),
     ImportedSpec(String),
 }
 
-// Import :
-// 	'import' (importURI=STRING|importedSpec=FQN);
-pub fn import(s: &str) -> IResult<&str, Import> {
-    let (s, _) = multispace0(s)?;
-    let (s, _) = tag("import")(s)?;
-    let (s, _) = multispace1(s)?;
-    todo!();
-}
-
-pub struct FdRootElement {}
-
-pub fn fd_root_element(s: &str) -> IResult<&str, FdRootElement> {
-    todo!()
-}
-
-// pub struct FdExtensionRoot {
-//     spec: FdSpecification,
-//     tag: Id,
-//     name: String,
-//     r#use: Vec<FdRootElement>,
-//     properties: FdPropertySet,
-//     elements: Vec<FdExtensionElement>,
-// }
-
+/// e.g. `specification org.example.spec extends org.example.base { for interfaces { ... } }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdSpecification {
+    pub name: String,
+    /// Possibly qualified name of the extended specification.
+    pub base: Option<String>,
+    pub declarations: Vec<FdDeclaration>,
+    pub span: Span,
+}
+
+/// Property declarations for one kind of deployed element, e.g. `for methods { ... }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdDeclaration {
+    pub host: FdPropertyHost,
+    pub properties: Vec<FdPropertyDecl>,
+    pub span: Span,
+}
+
+/// Kind of element a property is declared for.
+/// Franca User Guide Ref: 6.1.2 Deployment properties
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FdPropertyHost {
+    Providers,
+    Instances,
+    TypeCollections,
+    /// Whole Franca IDL interfaces, but not their instances
+    Interfaces,
+    Attributes,
+    Methods,
+    Broadcasts,
+    Arguments,
+    StructFields,
+    UnionFields,
+    Arrays,
+    Structs,
+    Unions,
+    Enumerations,
+    Enumerators,
+    TypeDefs,
+    Maps,
+    MapKeys,
+    MapValues,
+    Strings,
+    /// Integers and floats
+    Numbers,
+    Integers,
+    Floats,
+    Booleans,
+    ByteBuffers,
+    /// Host defined by a specification extension, e.g. `for devices`.
+    Extension(String),
+}
+
+impl FdPropertyHost {
+    /// Maps the name following `for` to its host,
+    /// unknown names are kept as [`FdPropertyHost::Extension`].
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "providers" => FdPropertyHost::Providers,
+            "instances" => FdPropertyHost::Instances,
+            "type_collections" => FdPropertyHost::TypeCollections,
+            "interfaces" => FdPropertyHost::Interfaces,
+            "attributes" => FdPropertyHost::Attributes,
+            "methods" => FdPropertyHost::Methods,
+            "broadcasts" => FdPropertyHost::Broadcasts,
+            "arguments" => FdPropertyHost::Arguments,
+            "struct_fields" => FdPropertyHost::StructFields,
+            "union_fields" => FdPropertyHost::UnionFields,
+            "arrays" => FdPropertyHost::Arrays,
+            "structs" => FdPropertyHost::Structs,
+            "unions" => FdPropertyHost::Unions,
+            "enumerations" => FdPropertyHost::Enumerations,
+            "enumerators" => FdPropertyHost::Enumerators,
+            "typedefs" => FdPropertyHost::TypeDefs,
+            "maps" => FdPropertyHost::Maps,
+            "map_keys" => FdPropertyHost::MapKeys,
+            "map_values" => FdPropertyHost::MapValues,
+            "strings" => FdPropertyHost::Strings,
+            "numbers" => FdPropertyHost::Numbers,
+            "integers" => FdPropertyHost::Integers,
+            "floats" => FdPropertyHost::Floats,
+            "booleans" => FdPropertyHost::Booleans,
+            "byte_buffers" => FdPropertyHost::ByteBuffers,
+            extension => FdPropertyHost::Extension(extension.to_string()),
+        }
+    }
+
+    /// Name following `for`.
+    pub fn name(&self) -> &str {
+        match self {
+            FdPropertyHost::Providers => "providers",
+            FdPropertyHost::Instances => "instances",
+            FdPropertyHost::TypeCollections => "type_collections",
+            FdPropertyHost::Interfaces => "interfaces",
+            FdPropertyHost::Attributes => "attributes",
+            FdPropertyHost::Methods => "methods",
+            FdPropertyHost::Broadcasts => "broadcasts",
+            FdPropertyHost::Arguments => "arguments",
+            FdPropertyHost::StructFields => "struct_fields",
+            FdPropertyHost::UnionFields => "union_fields",
+            FdPropertyHost::Arrays => "arrays",
+            FdPropertyHost::Structs => "structs",
+            FdPropertyHost::Unions => "unions",
+            FdPropertyHost::Enumerations => "enumerations",
+            FdPropertyHost::Enumerators => "enumerators",
+            FdPropertyHost::TypeDefs => "typedefs",
+            FdPropertyHost::Maps => "maps",
+            FdPropertyHost::MapKeys => "map_keys",
+            FdPropertyHost::MapValues => "map_values",
+            FdPropertyHost::Strings => "strings",
+            FdPropertyHost::Numbers => "numbers",
+            FdPropertyHost::Integers => "integers",
+            FdPropertyHost::Floats => "floats",
+            FdPropertyHost::Booleans => "booleans",
+            FdPropertyHost::ByteBuffers => "byte_buffers",
+            FdPropertyHost::Extension(name) => name,
+        }
+    }
+}
+
+/// e.g. `SomeIpServiceID : Integer ;` or
+/// `SomeIpStringEncoding : {utf8, utf16be, utf16le} (default: utf8);`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdPropertyDecl {
+    pub name: String,
+    pub r#type: FdTypeRef,
+    /// Properties are mandatory unless `optional` or given a default.
+    pub optional: Option<bool>,
+    pub default: Option<FdValue>,
+    pub span: Span,
+}
+
+impl FdPropertyDecl {
+    /// Whether deployments have to assign the property.
+    pub fn is_mandatory(&self) -> bool {
+        self.optional != Some(true) && self.default.is_none()
+    }
+}
+
+/// Type of a property declaration, either `predefined` or `complex`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdTypeRef {
+    pub predefined: Option<FdPredefinedTypeId>,
+    pub complex: Option<FdEnumType>,
+    /// Array of the type, e.g. `Integer[]`
+    pub array: bool,
+    pub span: Span,
+}
+
+impl fmt::Display for FdTypeRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.predefined, &self.complex) {
+            (Some(predefined), _) => write!(f, "{:?}", predefined)?,
+            (None, Some(complex)) => write!(f, "{{{}}}", complex.enumerators.join(", "))?,
+            (None, None) => {}
+        }
+        if self.array {
+            write!(f, "[]")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FdPredefinedTypeId {
+    Integer,
+    String,
+    Boolean,
+    /// Reference to a Franca IDL interface
+    Interface,
+}
+
+/// Inline enumeration, e.g. `{ utf8, utf16be, utf16le }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdEnumType {
+    pub enumerators: Vec<String>,
+}
+
+/// Deployment definition introduced by `define`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FdRootElement {
+    Interface(FdInterface),
+    TypeCollection(FdTypes),
+    Provider(FdProvider),
+    Extension(FdExtensionRoot),
+}
+
+impl FdRootElement {
+    /// Possibly qualified name of the deployment specification.
+    pub fn spec(&self) -> &str {
+        match self {
+            FdRootElement::Interface(interface) => &interface.spec,
+            FdRootElement::TypeCollection(types) => &types.spec,
+            FdRootElement::Provider(provider) => &provider.spec,
+            FdRootElement::Extension(extension) => &extension.spec,
+        }
+    }
+
+    /// Name given with `as`, used to refer to the deployment from `use`.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            FdRootElement::Interface(interface) => interface.name.as_deref(),
+            FdRootElement::TypeCollection(types) => types.name.as_deref(),
+            FdRootElement::Provider(provider) => provider.name.as_deref(),
+            FdRootElement::Extension(extension) => extension.name.as_deref(),
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            FdRootElement::Interface(interface) => interface.span,
+            FdRootElement::TypeCollection(types) => types.span,
+            FdRootElement::Provider(provider) => provider.span,
+            FdRootElement::Extension(extension) => extension.span,
+        }
+    }
+}
+
+/// e.g. `define org.example.spec for interface org.example.HelloWorld { ... }`
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdInterface {
-    spec: FdSpecification,
-    target: Option<FInterface>,
-    name: String,
-    r#use: Vec<FdRootElement>,
-    properties: FdPropertySet,
-    elements: Vec<FdAttribute>,
-    methods: Vec<FdMethod>,
-    broadcasts: Vec<FdBroadcast>,
-    types: Vec<FdTypeDefinition>,
-}
-
-pub struct FInterface {}
-
+    pub spec: String,
+    /// Possibly qualified name of the deployed interface.
+    pub target: String,
+    pub name: Option<String>,
+    /// Names of the deployments listed after `use`.
+    pub r#use: Vec<String>,
+    pub properties: FdPropertySet,
+    pub attributes: Vec<FdAttribute>,
+    pub methods: Vec<FdMethod>,
+    pub broadcasts: Vec<FdBroadcast>,
+    pub types: Vec<FdTypeDefinition>,
+    pub span: Span,
+}
+
+/// e.g. `define org.example.spec for typeCollection org.example.Types { ... }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdTypes {
+    pub spec: String,
+    /// Possibly qualified name of the deployed type collection,
+    /// `None` for anonymous type collections.
+    pub target: Option<String>,
+    pub name: Option<String>,
+    pub r#use: Vec<String>,
+    pub properties: FdPropertySet,
+    pub types: Vec<FdTypeDefinition>,
+    pub span: Span,
+}
+
+/// e.g. `define org.example.spec for provider as Service { instance org.example.HelloWorld { ... } }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdProvider {
+    pub spec: String,
+    pub name: Option<String>,
+    pub properties: FdPropertySet,
+    pub instances: Vec<FdInstance>,
+    pub span: Span,
+}
+
+/// Interface instance of a provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdInstance {
+    /// Possibly qualified name of the instantiated interface.
+    pub target: String,
+    pub name: Option<String>,
+    pub r#use: Vec<String>,
+    pub properties: FdPropertySet,
+    pub span: Span,
+}
+
+/// Deployment for a host defined by a specification extension,
+/// e.g. `define org.example.spec for device Gateway { ... }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdExtensionRoot {
+    pub spec: String,
+    pub tag: String,
+    pub name: Option<String>,
+    pub r#use: Vec<String>,
+    pub properties: FdPropertySet,
+    pub elements: Vec<FdExtensionElement>,
+    pub span: Span,
+}
+
+/// Nested element of an extension deployment, e.g. `port Diagnostics { ... }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdExtensionElement {
+    pub tag: String,
+    pub name: Option<String>,
+    pub properties: FdPropertySet,
+    pub elements: Vec<FdExtensionElement>,
+    pub span: Span,
+}
+
+/// e.g. `attribute speed { SomeIpGetterID = 1 }`
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdAttribute {
-    target: FAttribute,
-    properties: FdPropertySet,
-    overwrites: Option<FdTypeOverwrites>,
-}
-
-pub enum FdOperation {
-    FdMethod(FdMethod),
-    FdBroadcast(FdBroadcast),
+    pub target: String,
+    pub properties: FdPropertySet,
+    pub overwrites: Option<FdTypeOverwrites>,
+    pub span: Span,
 }
 
+/// e.g. `method sayHello { SomeIpMethodID = 1 in { name { ... } } }`
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdMethod {
-    target: FMethod,
-    properties: FdPropertySet,
-    r#in: Option<FdArgumentList>,
-    out: Option<FdArgumentList>,
+    /// Method name including the selector, e.g. `sayHello:v2`.
+    pub target: String,
+    pub properties: FdPropertySet,
+    pub r#in: Option<FdArgumentList>,
+    pub out: Option<FdArgumentList>,
+    pub span: Span,
 }
 
+/// e.g. `broadcast greeted { SomeIpEventID = 32769 }`
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdBroadcast {
-    target: FBroadcast,
-    properties: FdPropertySet,
-    out: Option<FdArgumentList>,
+    /// Broadcast name including the selector, e.g. `changed:v2`.
+    pub target: String,
+    pub properties: FdPropertySet,
+    pub out: Option<FdArgumentList>,
+    pub span: Span,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdArgumentList {
-    arguments: Vec<FdArgument>,
+    pub arguments: Vec<FdArgument>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdArgument {
-    target: FArgument,
-    properties: FdPropertySet,
-    overwrites: Option<FdTypeOverwrites>,
+    pub target: String,
+    pub properties: FdPropertySet,
+    pub overwrites: Option<FdTypeOverwrites>,
+    pub span: Span,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FdTypeDefinition {
-    FdArray(FdArray),
-    FdCompound(FdCompound),
-    FdEnumeration(FdEnumeration),
-    FdTypeDef(FdTypeDef),
-    FdMap(FdMap),
+    Array(FdArray),
+    Compound(FdCompound),
+    Enumeration(FdEnumeration),
+    TypeDef(FdTypeDef),
+    Map(FdMap),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FdCompound {
-    FdStruct(FdStruct),
-    FdUnion(FdUnion),
+    Struct(FdStruct),
+    Union(FdUnion),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdArray {
-    target: FArrayType,
-    properties: FdPropertySet,
-    overwrites: Option<FdTypeOverwrites>,
+    pub target: String,
+    pub properties: FdPropertySet,
+    pub overwrites: Option<FdTypeOverwrites>,
+    pub span: Span,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdStruct {
-    target: FStructType,
-    properties: FdPropertySet,
-    fields: Vec<FdField>,
+    pub target: String,
+    pub properties: FdPropertySet,
+    pub fields: Vec<FdField>,
+    pub span: Span,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdUnion {
-    target: FUnionType,
-    properties: FdPropertySet,
-    fields: Vec<FdField>,
+    pub target: String,
+    pub properties: FdPropertySet,
+    pub fields: Vec<FdField>,
+    pub span: Span,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdTypeDef {
-    target: FUnionType,
-    properties: FdPropertySet,
+    pub target: String,
+    pub properties: FdPropertySet,
+    pub span: Span,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdField {
-    target: FField,
-    properties: FdPropertySet,
-    overwrites: Option<FdTypeOverwrites>,
+    pub target: String,
+    pub properties: FdPropertySet,
+    pub overwrites: Option<FdTypeOverwrites>,
+    pub span: Span,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdEnumeration {
-    target: FEnumerationType,
-    properties: FdPropertySet,
-    enumerators: FdEnumValue,
+    pub target: String,
+    pub properties: FdPropertySet,
+    pub enumerators: Vec<FdEnumValue>,
+    pub span: Span,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdEnumValue {
-    target: FEnumerator,
-    properties: FdPropertySet,
+    pub target: String,
+    pub properties: FdPropertySet,
+    pub span: Span,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdMap {
-    target: FMapType,
-    properties: FdPropertySet,
-    key: Option<FdMapKey>,
-    value: Option<FdMapValue>,
+    pub target: String,
+    pub properties: FdPropertySet,
+    pub key: Option<FdMapKey>,
+    pub value: Option<FdMapValue>,
+    pub span: Span,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdMapKey {
-    properties: FdPropertySet,
+    pub properties: FdPropertySet,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FdMapValue {
-    properties: FdPropertySet,
+    pub properties: FdPropertySet,
 }
 
+/// Properties of the type of an attribute, argument, field or array element
+/// overwriting those of the type's own deployment, introduced by `#`.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FdTypeOverwrites {
-    FdPlainTypeOverwrites(FdPlainTypeOverwrites),
-    FdStructOverwrites(FdStructOverwrites),
-    FdUnionOverwrites(FdUnionOverwrites),
-    FdEnumerationOverwrites(FdEnumerationOverwrites),
+    Plain(FdPlainTypeOverwrites),
+    Struct(FdStructOverwrites),
+    Union(FdUnionOverwrites),
+    Enumeration(FdEnumerationOverwrites),
 }
 
-pub struct FdPlainTypeOverwrites {}
-
-pub struct FdStructOverwrites {}
-pub struct FdUnionOverwrites {}
-
-pub struct FdEnumerationOverwrites {}
-
-pub struct FdSpecification {}
-pub fn fd_specification(s: &str) -> IResult<&str, FdSpecification> {
-    todo!()
+/// e.g. `#{ SomeIpStringLength = 16 }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdPlainTypeOverwrites {
+    pub properties: FdPropertySet,
 }
 
-pub struct FdExtensionElement {}
-pub fn fd_extension_element(s: &str) -> IResult<&str, FdExtensionElement> {
-    todo!()
+/// e.g. `#struct { x { SomeIpIntegerBitWidth = 8 } }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdStructOverwrites {
+    pub properties: FdPropertySet,
+    pub fields: Vec<FdField>,
 }
 
-pub struct FdPropertySet {
-    items: Vec<FdProperty>,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdUnionOverwrites {
+    pub properties: FdPropertySet,
+    pub fields: Vec<FdField>,
 }
 
-pub struct FdProperty {
-    decl: FdPropertyDecl,
-    value: FdValue,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdEnumerationOverwrites {
+    pub properties: FdPropertySet,
+    pub enumerators: Vec<FdEnumValue>,
 }
 
-pub struct FdPropertyDecl {}
-
-pub enum FdValue {
-    FdInteger(i32),
-    FdString(String),
-    FdBoolean(bool),
-    FdInterfaceRef(FdInterfaceRef),
-    // FdGeneric(FdGeneric),
-}
-
-pub struct FdInterfaceRef {
-    value: FInterface,
-}
-
-// pub struct FdGeneric {
-//     value: EObject,
-// }
-
-pub fn fd_property_set(s: &str) -> IResult<&str, FdPropertySet> {
-    todo!()
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FdPropertySet {
+    pub items: Vec<FdProperty>,
 }
 
-pub fn fqn(s: &str) -> IResult<&str, &str> {
-    let (s, e_str) = take_till(|c: char| !c.is_alphanum() && c != '_' && c != '-')(s)?;
-    Ok((s, e_str))
+impl FdPropertySet {
+    /// Property assigned to the declaration `name`.
+    pub fn get(&self, name: &str) -> Option<&FdProperty> {
+        self.items.iter().find(|p| p.name == name)
+    }
 }
 
-pub fn fqn_with_selector(s: &str) -> IResult<&str, &str> {
-    let (s, _) = fqn(s)?;
-    let (s, _) = tag(":")(s)?;
-    let (s, _) = id(s)?;
-    todo!()
+/// e.g. `SomeIpServiceID = 4660`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdProperty {
+    /// Name of the property declaration in the specification,
+    /// see [`FdModel::property_value`]
+    pub name: String,
+    pub value: FdValue,
+    pub span: Span,
 }
 
-pub struct Id {}
-pub fn id(s: &str) -> IResult<&str, Id> {
-    todo!()
+/// Property value, names are parsed as [`FdValue::Generic`] and typed by
+/// [`FdPropertyDecl::typed_value`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FdValue {
+    /// Decimal or hexadecimal integer, e.g. `4660` or `0x1234`
+    Integer(i64),
+    String(String),
+    Boolean(bool),
+    /// Enumerator of an inline enumeration type, e.g. `utf16le`
+    Enumerator(String),
+    /// Possibly qualified name of a Franca IDL interface
+    InterfaceRef(String),
+    /// Possibly qualified name whose meaning depends on the type of the property.
+    Generic(String),
+    /// e.g. `{ 1, 2 }`
+    Array(Vec<FdValue>),
 }
@@ -0,0 +1,691 @@
+//! Resolution of deployment targets in the deployed Franca IDL model.
+
+use std::fmt;
+
+use super::{
+    FdArgument, FdArgumentList, FdAttribute, FdBroadcast, FdCompound, FdEnumValue, FdField,
+    FdInstance, FdInterface, FdMethod, FdModel, FdRootElement, FdTypeDefinition, FdTypeOverwrites,
+    FdTypes,
+};
+use crate::{
+    FArgument, FAttribute, FBroadcast, FContainer, FEnumerator, FField, FInterface, FMethod,
+    FModel, FType, FTypeCollection, FTypeRef, Span,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkError {
+    pub kind: LinkErrorKind,
+    /// Deployment element referencing the missing target
+    pub span: Span,
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.span.line, self.span.column, self.kind)
+    }
+}
+
+impl std::error::Error for LinkError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkErrorKind {
+    UnknownInterface(String),
+    UnknownTypeCollection(String),
+    UnknownAttribute(String),
+    UnknownMethod(String),
+    UnknownBroadcast(String),
+    UnknownArgument(String),
+    /// Type of the deployed kind, e.g. `struct`, does not exist.
+    UnknownType {
+        kind: &'static str,
+        name: String,
+    },
+    UnknownField(String),
+    UnknownEnumerator(String),
+}
+
+impl fmt::Display for LinkErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkErrorKind::UnknownInterface(name) => write!(f, "unknown interface `{}`", name),
+            LinkErrorKind::UnknownTypeCollection(name) => {
+                write!(f, "unknown type collection `{}`", name)
+            }
+            LinkErrorKind::UnknownAttribute(name) => write!(f, "unknown attribute `{}`", name),
+            LinkErrorKind::UnknownMethod(name) => write!(f, "unknown method `{}`", name),
+            LinkErrorKind::UnknownBroadcast(name) => write!(f, "unknown broadcast `{}`", name),
+            LinkErrorKind::UnknownArgument(name) => write!(f, "unknown argument `{}`", name),
+            LinkErrorKind::UnknownType { kind, name } => write!(f, "unknown {} `{}`", kind, name),
+            LinkErrorKind::UnknownField(name) => write!(f, "unknown field `{}`", name),
+            LinkErrorKind::UnknownEnumerator(name) => write!(f, "unknown enumerator `{}`", name),
+        }
+    }
+}
+
+impl FdInterface {
+    pub fn resolve<'a>(&self, model: &'a FModel) -> Option<&'a FInterface> {
+        model.find_interface(&self.target)
+    }
+}
+
+impl FdInstance {
+    pub fn resolve<'a>(&self, model: &'a FModel) -> Option<&'a FInterface> {
+        model.find_interface(&self.target)
+    }
+}
+
+impl FdTypes {
+    /// Type collection named by the target, optionally qualified by the package name,
+    /// or the anonymous type collection if there is no target.
+    pub fn resolve<'a>(&self, model: &'a FModel) -> Option<&'a FTypeCollection> {
+        let name = self.target.as_deref().map(|target| {
+            target
+                .strip_prefix(model.name.as_str())
+                .and_then(|t| t.strip_prefix('.'))
+                .unwrap_or(target)
+        });
+        model
+            .type_collections
+            .iter()
+            .find(|t| t.name.as_deref() == name)
+    }
+}
+
+impl FdAttribute {
+    /// Attribute of `interface` including inherited ones.
+    pub fn resolve<'a>(
+        &self,
+        model: &'a FModel,
+        interface: &'a FInterface,
+    ) -> Option<&'a FAttribute> {
+        model
+            .all_attributes(interface)
+            .into_iter()
+            .find(|a| a.name == self.target)
+    }
+}
+
+impl FdMethod {
+    /// Method of `interface` including inherited ones, matched by name and selector.
+    pub fn resolve<'a>(&self, model: &'a FModel, interface: &'a FInterface) -> Option<&'a FMethod> {
+        model
+            .all_methods(interface)
+            .into_iter()
+            .find(|m| m.name_with_selector() == self.target)
+    }
+}
+
+impl FdBroadcast {
+    /// Broadcast of `interface` including inherited ones, matched by name and selector.
+    pub fn resolve<'a>(
+        &self,
+        model: &'a FModel,
+        interface: &'a FInterface,
+    ) -> Option<&'a FBroadcast> {
+        model
+            .all_broadcasts(interface)
+            .into_iter()
+            .find(|b| b.name_with_selector() == self.target)
+    }
+}
+
+impl FdArgument {
+    pub fn resolve<'a>(&self, arguments: &'a [FArgument]) -> Option<&'a FArgument> {
+        arguments.iter().find(|a| a.name == self.target)
+    }
+}
+
+impl FdField {
+    pub fn resolve<'a>(&self, fields: &[&'a FField]) -> Option<&'a FField> {
+        fields.iter().copied().find(|f| f.name == self.target)
+    }
+}
+
+impl FdEnumValue {
+    pub fn resolve<'a>(&self, enumerators: &[&'a FEnumerator]) -> Option<&'a FEnumerator> {
+        enumerators.iter().copied().find(|e| e.name == self.target)
+    }
+}
+
+impl FdTypeDefinition {
+    /// Name of the deployed type.
+    pub fn target(&self) -> &str {
+        match self {
+            FdTypeDefinition::Array(array) => &array.target,
+            FdTypeDefinition::Compound(FdCompound::Struct(r#struct)) => &r#struct.target,
+            FdTypeDefinition::Compound(FdCompound::Union(union)) => &union.target,
+            FdTypeDefinition::Enumeration(enumeration) => &enumeration.target,
+            FdTypeDefinition::TypeDef(type_def) => &type_def.target,
+            FdTypeDefinition::Map(map) => &map.target,
+        }
+    }
+
+    /// Keyword of the deployed kind of type, e.g. `struct`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FdTypeDefinition::Array(_) => "array",
+            FdTypeDefinition::Compound(FdCompound::Struct(_)) => "struct",
+            FdTypeDefinition::Compound(FdCompound::Union(_)) => "union",
+            FdTypeDefinition::Enumeration(_) => "enumeration",
+            FdTypeDefinition::TypeDef(_) => "typedef",
+            FdTypeDefinition::Map(_) => "map",
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            FdTypeDefinition::Array(array) => array.span,
+            FdTypeDefinition::Compound(FdCompound::Struct(r#struct)) => r#struct.span,
+            FdTypeDefinition::Compound(FdCompound::Union(union)) => union.span,
+            FdTypeDefinition::Enumeration(enumeration) => enumeration.span,
+            FdTypeDefinition::TypeDef(type_def) => type_def.span,
+            FdTypeDefinition::Map(map) => map.span,
+        }
+    }
+
+    /// Type among `types` of the same name and kind.
+    pub fn resolve<'a>(&self, types: &'a [FType]) -> Option<&'a FType> {
+        types.iter().find(|t| {
+            let same_kind = matches!(
+                (self, t),
+                (FdTypeDefinition::Array(_), FType::Array(_))
+                    | (
+                        FdTypeDefinition::Compound(FdCompound::Struct(_)),
+                        FType::Struct(_)
+                    )
+                    | (
+                        FdTypeDefinition::Compound(FdCompound::Union(_)),
+                        FType::Union(_)
+                    )
+                    | (FdTypeDefinition::Enumeration(_), FType::Enumeration(_))
+                    | (FdTypeDefinition::TypeDef(_), FType::TypeDef(_))
+                    | (FdTypeDefinition::Map(_), FType::Map(_))
+            );
+            same_kind && t.name() == self.target()
+        })
+    }
+}
+
+impl FdModel {
+    /// Resolves the targets of all deployments in `model`, returning the
+    /// deployment elements whose interface, type collection, attribute, method,
+    /// broadcast, argument, type, field or enumerator does not exist.
+    pub fn link(&self, model: &FModel) -> Vec<LinkError> {
+        let mut linker = Linker {
+            model,
+            errors: Vec::new(),
+        };
+        for deployment in &self.deployments {
+            linker.root_element(deployment);
+        }
+        linker.errors
+    }
+}
+
+struct Linker<'a> {
+    model: &'a FModel,
+    errors: Vec<LinkError>,
+}
+
+impl<'a> Linker<'a> {
+    fn root_element(&mut self, deployment: &FdRootElement) {
+        match deployment {
+            FdRootElement::Interface(deployment) => match deployment.resolve(self.model) {
+                Some(interface) => self.interface(deployment, interface),
+                None => self.error(
+                    LinkErrorKind::UnknownInterface(deployment.target.clone()),
+                    deployment.span,
+                ),
+            },
+            FdRootElement::TypeCollection(deployment) => match deployment.resolve(self.model) {
+                Some(type_collection) => self.types(
+                    &deployment.types,
+                    FContainer::TypeCollection(type_collection),
+                ),
+                None => self.error(
+                    LinkErrorKind::UnknownTypeCollection(
+                        deployment.target.clone().unwrap_or_default(),
+                    ),
+                    deployment.span,
+                ),
+            },
+            FdRootElement::Provider(provider) => {
+                for instance in &provider.instances {
+                    if instance.resolve(self.model).is_none() {
+                        self.error(
+                            LinkErrorKind::UnknownInterface(instance.target.clone()),
+                            instance.span,
+                        );
+                    }
+                }
+            }
+            FdRootElement::Extension(_) => {}
+        }
+    }
+
+    fn interface(&mut self, deployment: &FdInterface, interface: &'a FInterface) {
+        for attribute in &deployment.attributes {
+            match attribute.resolve(self.model, interface) {
+                Some(target) => self.overwrites(
+                    attribute.overwrites.as_ref(),
+                    &target.r#type,
+                    target.array,
+                    self.declaring(interface, |i| {
+                        i.attributes.iter().any(|a| std::ptr::eq(a, target))
+                    }),
+                ),
+                None => self.error(
+                    LinkErrorKind::UnknownAttribute(attribute.target.clone()),
+                    attribute.span,
+                ),
+            }
+        }
+        for method in &deployment.methods {
+            match method.resolve(self.model, interface) {
+                Some(target) => {
+                    let container = self.declaring(interface, |i| {
+                        i.methods.iter().any(|m| std::ptr::eq(m, target))
+                    });
+                    self.arguments(method.r#in.as_ref(), &target.in_args, container);
+                    self.arguments(method.out.as_ref(), &target.out_args, container);
+                }
+                None => self.error(
+                    LinkErrorKind::UnknownMethod(method.target.clone()),
+                    method.span,
+                ),
+            }
+        }
+        for broadcast in &deployment.broadcasts {
+            match broadcast.resolve(self.model, interface) {
+                Some(target) => self.arguments(
+                    broadcast.out.as_ref(),
+                    &target.out_args,
+                    self.declaring(interface, |i| {
+                        i.broadcasts.iter().any(|b| std::ptr::eq(b, target))
+                    }),
+                ),
+                None => self.error(
+                    LinkErrorKind::UnknownBroadcast(broadcast.target.clone()),
+                    broadcast.span,
+                ),
+            }
+        }
+        self.types(&deployment.types, FContainer::Interface(interface));
+    }
+
+    /// Interface of the hierarchy of `interface` declaring the member `declares` holds for.
+    fn declaring(
+        &self,
+        interface: &'a FInterface,
+        declares: impl Fn(&FInterface) -> bool,
+    ) -> FContainer<'a> {
+        let declaring = self
+            .model
+            .interface_hierarchy(interface)
+            .into_iter()
+            .find(|i| declares(i))
+            .unwrap_or(interface);
+        FContainer::Interface(declaring)
+    }
+
+    fn arguments(
+        &mut self,
+        deployments: Option<&FdArgumentList>,
+        arguments: &'a [FArgument],
+        container: FContainer<'a>,
+    ) {
+        for deployment in deployments.iter().flat_map(|d| d.arguments.iter()) {
+            match deployment.resolve(arguments) {
+                Some(target) => self.overwrites(
+                    deployment.overwrites.as_ref(),
+                    &target.r#type,
+                    target.array,
+                    container,
+                ),
+                None => self.error(
+                    LinkErrorKind::UnknownArgument(deployment.target.clone()),
+                    deployment.span,
+                ),
+            }
+        }
+    }
+
+    fn types(&mut self, deployments: &[FdTypeDefinition], container: FContainer<'a>) {
+        for deployment in deployments {
+            let target = match deployment.resolve(container.types()) {
+                Some(target) => target,
+                None => {
+                    self.error(
+                        LinkErrorKind::UnknownType {
+                            kind: deployment.kind(),
+                            name: deployment.target().to_string(),
+                        },
+                        deployment.span(),
+                    );
+                    continue;
+                }
+            };
+            match (deployment, target) {
+                // the element type is already unwrapped, even if it is a named array itself
+                (FdTypeDefinition::Array(array), FType::Array(target)) => self.overwrites(
+                    array.overwrites.as_ref(),
+                    &target.element_type,
+                    true,
+                    container,
+                ),
+                (
+                    FdTypeDefinition::Compound(FdCompound::Struct(r#struct)),
+                    FType::Struct(target),
+                ) => self.fields(&r#struct.fields, &self.model.all_struct_fields(target)),
+                (FdTypeDefinition::Compound(FdCompound::Union(union)), FType::Union(target)) => {
+                    self.fields(&union.fields, &self.model.all_union_fields(target))
+                }
+                (FdTypeDefinition::Enumeration(enumeration), FType::Enumeration(target)) => self
+                    .enumerators(
+                        &enumeration.enumerators,
+                        &self.model.all_enumerators(target),
+                    ),
+                _ => {}
+            }
+        }
+    }
+
+    fn fields(&mut self, deployments: &[FdField], fields: &[&'a FField]) {
+        for deployment in deployments {
+            match deployment.resolve(fields) {
+                Some(target) => {
+                    // inherited fields are declared by the container of the base type
+                    let declares = |t: &FType| match t {
+                        FType::Struct(s) => s.elements.iter().any(|f| std::ptr::eq(f, target)),
+                        FType::Union(u) => u.elements.iter().any(|f| std::ptr::eq(f, target)),
+                        _ => false,
+                    };
+                    if let Some(container) = self.model.type_container(declares) {
+                        self.overwrites(
+                            deployment.overwrites.as_ref(),
+                            &target.r#type,
+                            target.array,
+                            container,
+                        )
+                    }
+                }
+                None => self.error(
+                    LinkErrorKind::UnknownField(deployment.target.clone()),
+                    deployment.span,
+                ),
+            }
+        }
+    }
+
+    fn enumerators(&mut self, deployments: &[FdEnumValue], enumerators: &[&'a FEnumerator]) {
+        for deployment in deployments {
+            if deployment.resolve(enumerators).is_none() {
+                self.error(
+                    LinkErrorKind::UnknownEnumerator(deployment.target.clone()),
+                    deployment.span,
+                );
+            }
+        }
+    }
+
+    /// Checks the fields or enumerators of overwrites against the type of the
+    /// overwritten element, array elements for implicit arrays.
+    fn overwrites(
+        &mut self,
+        overwrites: Option<&FdTypeOverwrites>,
+        type_ref: &'a FTypeRef,
+        implicit_array: bool,
+        container: FContainer<'a>,
+    ) {
+        let overwrites = match overwrites {
+            Some(overwrites) => overwrites,
+            None => return,
+        };
+        let (type_ref, container) = match implicit_array {
+            true => (type_ref, container),
+            false => self
+                .model
+                .array_element_type(type_ref, false, container)
+                .unwrap_or((type_ref, container)),
+        };
+        match (overwrites, self.actual_type(type_ref, container)) {
+            (FdTypeOverwrites::Struct(r#struct), Some(FType::Struct(target))) => {
+                self.fields(&r#struct.fields, &self.model.all_struct_fields(target))
+            }
+            (FdTypeOverwrites::Union(union), Some(FType::Union(target))) => {
+                self.fields(&union.fields, &self.model.all_union_fields(target))
+            }
+            (FdTypeOverwrites::Enumeration(enumeration), Some(FType::Enumeration(target))) => self
+                .enumerators(
+                    &enumeration.enumerators,
+                    &self.model.all_enumerators(target),
+                ),
+            _ => {}
+        }
+    }
+
+    /// Type referenced by `type_ref` within `container` with typedefs followed.
+    fn actual_type(&self, type_ref: &'a FTypeRef, container: FContainer<'a>) -> Option<&'a FType> {
+        let (mut container, mut r#type) = self
+            .model
+            .lookup_type(type_ref.derived.as_deref()?, Some(container))
+            .ok()?;
+        let mut visited = Vec::new();
+        while let FType::TypeDef(type_def) = r#type {
+            if visited.iter().any(|t| std::ptr::eq(*t, type_def)) {
+                return None;
+            }
+            visited.push(type_def);
+            let name = type_def.actual_type.derived.as_deref()?;
+            (container, r#type) = self.model.lookup_type(name, Some(container)).ok()?;
+        }
+        Some(r#type)
+    }
+
+    fn error(&mut self, kind: LinkErrorKind, span: Span) {
+        self.errors.push(LinkError { kind, span });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LinkErrorKind;
+    use crate::{parse_fdepl, parse_fidl};
+
+    const MODEL: &str = r#"package org.example
+
+interface Base {
+    attribute Int32 counter
+}
+
+interface HelloWorld extends Base {
+    method sayHello {
+        in {
+            Greeting greeting
+        }
+        out {
+            String reply
+        }
+    }
+
+    broadcast changed {
+        out {
+            Color color
+        }
+    }
+
+    struct Greeting {
+        String name
+        Color color
+    }
+
+    enumeration Color {
+        Red
+        Green
+    }
+}
+
+typeCollection Types {
+    union Value {
+        Int32 number
+        String text
+    }
+}
+"#;
+
+    const DEPLOYMENT: &str = r#"package org.example.deployment
+
+define org.example.spec for interface org.example.HelloWorld {
+    attribute counter {
+    }
+    attribute missing {
+    }
+
+    method sayHello {
+        in {
+            greeting {
+                #struct {
+                    name {
+                    }
+                    title {
+                    }
+                }
+            }
+            other {
+            }
+        }
+    }
+
+    method sayBye {
+    }
+
+    broadcast changed {
+        out {
+            color {
+                #enumeration {
+                    Red {
+                    }
+                    Blue {
+                    }
+                }
+            }
+        }
+    }
+
+    struct Greeting {
+        color {
+        }
+        age {
+        }
+    }
+
+    union Greeting {
+    }
+}
+
+define org.example.spec for typeCollection org.example.Types {
+    union Value {
+        text {
+        }
+    }
+}
+
+define org.example.spec for typeCollection Missing {
+}
+
+define org.example.spec for interface org.example.Missing {
+}
+"#;
+
+    #[test]
+    pub fn link_test() {
+        let model = parse_fidl(MODEL).unwrap();
+        let deployment = parse_fdepl(DEPLOYMENT).unwrap();
+
+        let errors = deployment.link(&model);
+        let errors: Vec<_> = errors
+            .iter()
+            .map(|e| (e.span.line, e.span.column, e.kind.clone()))
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                (6, 5, LinkErrorKind::UnknownAttribute("missing".to_string())),
+                (15, 21, LinkErrorKind::UnknownField("title".to_string())),
+                (19, 13, LinkErrorKind::UnknownArgument("other".to_string())),
+                (24, 5, LinkErrorKind::UnknownMethod("sayBye".to_string())),
+                (33, 21, LinkErrorKind::UnknownEnumerator("Blue".to_string())),
+                (43, 9, LinkErrorKind::UnknownField("age".to_string())),
+                (
+                    47,
+                    5,
+                    LinkErrorKind::UnknownType {
+                        kind: "union",
+                        name: "Greeting".to_string(),
+                    }
+                ),
+                (
+                    58,
+                    1,
+                    LinkErrorKind::UnknownTypeCollection("Missing".to_string())
+                ),
+                (
+                    61,
+                    1,
+                    LinkErrorKind::UnknownInterface("org.example.Missing".to_string())
+                ),
+            ]
+        );
+
+        let error = &deployment.link(&model)[0];
+        assert_eq!(error.to_string(), "6:5: unknown attribute `missing`");
+    }
+
+    #[test]
+    pub fn link_nested_array_test() {
+        let model = "package org.example
+
+typeCollection Types {
+    struct Point {
+        Int32 x
+        Int32 y
+    }
+
+    array Points of Point
+    array Rows of Points
+}
+";
+        let deployment = "package org.example.deployment
+
+define org.example.spec for typeCollection org.example.Types {
+    array Points {
+        #struct {
+            x {
+            }
+            z {
+            }
+        }
+    }
+
+    array Rows {
+        #struct {
+            z {
+            }
+        }
+    }
+}
+";
+        let model = parse_fidl(model).unwrap();
+        let deployment = parse_fdepl(deployment).unwrap();
+
+        let errors = deployment.link(&model);
+        let errors: Vec<_> = errors
+            .iter()
+            .map(|e| (e.span.line, e.span.column, e.kind.clone()))
+            .collect();
+        assert_eq!(
+            errors,
+            [(8, 13, LinkErrorKind::UnknownField("z".to_string()))]
+        );
+    }
+}
@@ -0,0 +1,1088 @@
+use nom::{
+    branch::alt,
+    character::complete::char,
+    combinator::{cut, eof, map, map_res, not, opt, peek, recognize, value},
+    error::context,
+    multi::{many0, separated_list0, separated_list1},
+    sequence::{delimited, pair, preceded, terminated, tuple},
+};
+
+use super::{
+    FdArgument, FdArgumentList, FdArray, FdAttribute, FdBroadcast, FdCompound, FdDeclaration,
+    FdEnumType, FdEnumValue, FdEnumeration, FdEnumerationOverwrites, FdExtensionElement,
+    FdExtensionRoot, FdField, FdInstance, FdInterface, FdMap, FdMapKey, FdMapValue, FdMethod,
+    FdModel, FdPlainTypeOverwrites, FdPredefinedTypeId, FdProperty, FdPropertyDecl, FdPropertyHost,
+    FdPropertySet, FdProvider, FdRootElement, FdSpecification, FdStruct, FdStructOverwrites,
+    FdTypeDef, FdTypeDefinition, FdTypeOverwrites, FdTypeRef, FdTypes, FdUnion, FdUnionOverwrites,
+    FdValue, Import,
+};
+use crate::error::ParseError;
+use crate::parser::{
+    brc_close, brc_open, committed, element, expect, extends, fqn, identifier, implicit_array,
+    integer, items_until, keyword, parse_complete, selector, sp, span, string_literal, IResult,
+    Input,
+};
+
+/// Parses a `.fdepl` file, errors are located by line and column within `s`.
+pub fn parse_fdepl(s: &str) -> Result<FdModel, ParseError> {
+    parse_complete(fd_model, s)
+}
+
+/// Package, imports, specifications and deployments. Fails at the first construct
+/// that is neither, unless only whitespace and comments are left.
+pub fn fd_model(s: Input) -> IResult<Input, FdModel> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("package")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = fqn(s)?;
+
+    let (s, imports) = many0(element("import", import))(s)?;
+    let (s, (elements, _)) = items_until(
+        fd_model_element,
+        peek(pair(sp, expect("end of input", eof))),
+    )(s)?;
+
+    let mut specifications = Vec::new();
+    let mut deployments = Vec::new();
+    for element in elements {
+        match element {
+            FdModelElement::Specification(specification) => specifications.push(specification),
+            FdModelElement::Deployment(deployment) => deployments.push(deployment),
+        }
+    }
+
+    Ok((
+        s,
+        FdModel {
+            name: name.to_string(),
+            imports,
+            specifications,
+            deployments,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// Top level elements that may appear in any order after the imports.
+enum FdModelElement {
+    Specification(FdSpecification),
+    Deployment(FdRootElement),
+}
+
+fn fd_model_element(s: Input) -> IResult<Input, FdModelElement> {
+    alt((
+        map(
+            element("specification", fd_specification),
+            FdModelElement::Specification,
+        ),
+        map(fd_root_element, FdModelElement::Deployment),
+    ))(s)
+}
+
+/// e.g. `import "deployment_spec.fdepl"` or `import org.example.spec`
+pub fn import(s: Input) -> IResult<Input, Import> {
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("import")(s)?;
+    let (s, _) = sp(s)?;
+    alt((
+        map(string_literal, Import::ImportUri),
+        map(fqn, |spec| Import::ImportedSpec(spec.to_string())),
+    ))(s)
+}
+
+/// e.g. `specification org.example.spec extends org.example.base { for interfaces { ... } }`
+pub fn fd_specification(s: Input) -> IResult<Input, FdSpecification> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("specification")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = fqn(s)?;
+    let (s, base) = opt(extends)(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, (declarations, _)) = items_until(element("for", fd_declaration), brc_close)(s)?;
+    Ok((
+        s,
+        FdSpecification {
+            name: name.to_string(),
+            base: base.map(|b| b.to_string()),
+            declarations,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// e.g. `for strings { SomeIpStringEncoding : {utf8, utf16le} (default: utf8); }`
+pub fn fd_declaration(s: Input) -> IResult<Input, FdDeclaration> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("for")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, host) = identifier(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, (properties, _)) = items_until(fd_property_decl, brc_close)(s)?;
+    Ok((
+        s,
+        FdDeclaration {
+            host: FdPropertyHost::from_name(&host),
+            properties,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// e.g. `SomeIpEventGroups : Integer[] (optional);`, the `;` may be omitted.
+///
+/// Like FDeploy's `'(' flags+=FDPropertyFlag (',' flags+=FDPropertyFlag)* ')'`
+/// all flags share one pair of parentheses, e.g. `(optional, default: 0)`.
+pub fn fd_property_decl(s: Input) -> IResult<Input, FdPropertyDecl> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, name) = identifier(s)?;
+    let (s, _) = tuple((sp, char(':'), sp))(s)?;
+    let (s, r#type) = cut(fd_type_ref)(s)?;
+    let (s, flags) = opt(delimited(
+        pair(sp, char('(')),
+        separated_list1(pair(sp, char(',')), preceded(sp, fd_property_flag)),
+        pair(sp, char(')')),
+    ))(s)?;
+    let end = s;
+    let (s, _) = opt(preceded(sp, char(';')))(s)?;
+
+    let mut optional = None;
+    let mut default = None;
+    for flag in flags.unwrap_or_default() {
+        match flag {
+            FdPropertyFlag::Optional => optional = Some(true),
+            FdPropertyFlag::Default(value) => default = Some(value),
+        }
+    }
+
+    Ok((
+        s,
+        FdPropertyDecl {
+            name: name.to_string(),
+            r#type,
+            optional,
+            default,
+            span: span(start, end),
+        },
+    ))
+}
+
+#[derive(Clone)]
+enum FdPropertyFlag {
+    Optional,
+    Default(FdValue),
+}
+
+/// `optional` or e.g. `default: utf8`
+fn fd_property_flag(s: Input) -> IResult<Input, FdPropertyFlag> {
+    alt((
+        value(FdPropertyFlag::Optional, keyword("optional")),
+        map(
+            preceded(tuple((keyword("default"), sp, char(':'), sp)), fd_value),
+            FdPropertyFlag::Default,
+        ),
+    ))(s)
+}
+
+/// e.g. `Integer`, `String[]` or `{ utf8, utf16le }`
+pub fn fd_type_ref(s: Input) -> IResult<Input, FdTypeRef> {
+    let start = s;
+    let (s, predefined) = opt(alt((
+        value(FdPredefinedTypeId::Integer, keyword("Integer")),
+        value(FdPredefinedTypeId::String, keyword("String")),
+        value(FdPredefinedTypeId::Boolean, keyword("Boolean")),
+        value(FdPredefinedTypeId::Interface, keyword("Interface")),
+    )))(s)?;
+    let (s, complex) = match predefined {
+        Some(_) => (s, None),
+        None => map(fd_enum_type, Some)(s)?,
+    };
+    let (s, array) = opt(implicit_array)(s)?;
+    Ok((
+        s,
+        FdTypeRef {
+            predefined,
+            complex,
+            array: array.is_some(),
+            span: span(start, s),
+        },
+    ))
+}
+
+/// Enumerators may optionally be separated by commas.
+fn fd_enum_type(s: Input) -> IResult<Input, FdEnumType> {
+    let (s, _) = char('{')(s)?;
+    let (s, (enumerators, _)) = items_until(
+        terminated(preceded(sp, identifier), opt(pair(sp, char(',')))),
+        brc_close,
+    )(s)?;
+    Ok((
+        s,
+        FdEnumType {
+            enumerators: enumerators.into_iter().map(|e| e.to_string()).collect(),
+        },
+    ))
+}
+
+pub fn fd_root_element(s: Input) -> IResult<Input, FdRootElement> {
+    alt((
+        map(
+            deployment("interface", context("interface deployment", fd_interface)),
+            FdRootElement::Interface,
+        ),
+        map(
+            deployment(
+                "typeCollection",
+                context("typeCollection deployment", fd_types),
+            ),
+            FdRootElement::TypeCollection,
+        ),
+        map(
+            deployment("provider", context("provider deployment", fd_provider)),
+            FdRootElement::Provider,
+        ),
+        map(
+            committed("define", context("deployment", fd_extension_root)),
+            FdRootElement::Extension,
+        ),
+    ))(s)
+}
+
+/// Commits to `parser` once `define <spec> for <host>` is found.
+fn deployment<'a, O>(
+    host: &'static str,
+    parser: impl FnMut(Input<'a>) -> IResult<Input<'a>, O>,
+) -> impl FnMut(Input<'a>) -> IResult<Input<'a>, O> {
+    preceded(peek(pair(sp, define(host))), cut(parser))
+}
+
+/// `define <spec> for <host>` header of a deployment, returns the specification name.
+fn define<'a>(host: &'static str) -> impl FnMut(Input<'a>) -> IResult<Input<'a>, Input<'a>> {
+    delimited(
+        pair(keyword("define"), sp),
+        fqn,
+        tuple((sp, keyword("for"), sp, keyword(host))),
+    )
+}
+
+/// Name given to a deployment, e.g. ` as HelloWorldDeployment`.
+fn alias(s: Input) -> IResult<Input, Option<Input>> {
+    opt(preceded(tuple((sp, keyword("as"), sp)), identifier))(s)
+}
+
+/// Deployments referenced by `use`.
+fn uses(s: Input) -> IResult<Input, Vec<String>> {
+    many0(map(
+        preceded(tuple((sp, keyword("use"), sp)), fqn),
+        |r#use| r#use.to_string(),
+    ))(s)
+}
+
+/// e.g. `define org.example.spec for interface org.example.HelloWorld { ... }`
+pub fn fd_interface(s: Input) -> IResult<Input, FdInterface> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, spec) = define("interface")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, target) = fqn(s)?;
+    let (s, name) = alias(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, r#use) = uses(s)?;
+    let (s, properties) = fd_property_set(s)?;
+    let (s, (members, _)) = items_until(fd_interface_member, brc_close)(s)?;
+
+    let mut attributes = Vec::new();
+    let mut methods = Vec::new();
+    let mut broadcasts = Vec::new();
+    let mut types = Vec::new();
+    for member in members {
+        match member {
+            FdInterfaceMember::Attribute(attribute) => attributes.push(attribute),
+            FdInterfaceMember::Method(method) => methods.push(method),
+            FdInterfaceMember::Broadcast(broadcast) => broadcasts.push(broadcast),
+            FdInterfaceMember::Type(r#type) => types.push(r#type),
+        }
+    }
+
+    Ok((
+        s,
+        FdInterface {
+            spec: spec.to_string(),
+            target: target.to_string(),
+            name: name.map(|n| n.to_string()),
+            r#use,
+            properties,
+            attributes,
+            methods,
+            broadcasts,
+            types,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// Elements that may appear in any order within an interface deployment.
+enum FdInterfaceMember {
+    Attribute(FdAttribute),
+    Method(FdMethod),
+    Broadcast(FdBroadcast),
+    Type(FdTypeDefinition),
+}
+
+fn fd_interface_member(s: Input) -> IResult<Input, FdInterfaceMember> {
+    alt((
+        map(
+            element("attribute", fd_attribute),
+            FdInterfaceMember::Attribute,
+        ),
+        map(element("method", fd_method), FdInterfaceMember::Method),
+        map(
+            element("broadcast", fd_broadcast),
+            FdInterfaceMember::Broadcast,
+        ),
+        map(fd_type_definition, FdInterfaceMember::Type),
+    ))(s)
+}
+
+/// e.g. `define org.example.spec for typeCollection org.example.Types { ... }`
+pub fn fd_types(s: Input) -> IResult<Input, FdTypes> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, spec) = define("typeCollection")(s)?;
+    let (s, target) = opt(preceded(pair(sp, not(keyword("as"))), fqn))(s)?;
+    let (s, name) = alias(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, r#use) = uses(s)?;
+    let (s, properties) = fd_property_set(s)?;
+    let (s, (types, _)) = items_until(fd_type_definition, brc_close)(s)?;
+    Ok((
+        s,
+        FdTypes {
+            spec: spec.to_string(),
+            target: target.map(|t| t.to_string()),
+            name: name.map(|n| n.to_string()),
+            r#use,
+            properties,
+            types,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// e.g. `define org.example.spec for provider as Service { instance org.example.HelloWorld { ... } }`
+pub fn fd_provider(s: Input) -> IResult<Input, FdProvider> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, spec) = define("provider")(s)?;
+    let (s, name) = alias(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, properties) = fd_property_set(s)?;
+    let (s, (instances, _)) = items_until(element("instance", fd_instance), brc_close)(s)?;
+    Ok((
+        s,
+        FdProvider {
+            spec: spec.to_string(),
+            name: name.map(|n| n.to_string()),
+            properties,
+            instances,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// e.g. `instance org.example.HelloWorld as Primary { InstanceId = "primary" }`
+pub fn fd_instance(s: Input) -> IResult<Input, FdInstance> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("instance")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, target) = fqn(s)?;
+    let (s, name) = alias(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, r#use) = uses(s)?;
+    let (s, properties) = fd_property_set(s)?;
+    let (s, _) = brc_close(s)?;
+    Ok((
+        s,
+        FdInstance {
+            target: target.to_string(),
+            name: name.map(|n| n.to_string()),
+            r#use,
+            properties,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// e.g. `define org.example.spec for device Gateway { port Diagnostics { ... } }`
+pub fn fd_extension_root(s: Input) -> IResult<Input, FdExtensionRoot> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("define")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, spec) = fqn(s)?;
+    let (s, _) = tuple((sp, keyword("for"), sp))(s)?;
+    let (s, tag) = identifier(s)?;
+    let (s, name) = opt(preceded(sp, identifier))(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, r#use) = uses(s)?;
+    let (s, properties) = fd_property_set(s)?;
+    let (s, (elements, _)) = items_until(fd_extension_element, brc_close)(s)?;
+    Ok((
+        s,
+        FdExtensionRoot {
+            spec: spec.to_string(),
+            tag: tag.to_string(),
+            name: name.map(|n| n.to_string()),
+            r#use,
+            properties,
+            elements,
+            span: span(start, s),
+        },
+    ))
+}
+
+pub fn fd_extension_element(s: Input) -> IResult<Input, FdExtensionElement> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, tag) = identifier(s)?;
+    let (s, name) = opt(preceded(sp, identifier))(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, properties) = fd_property_set(s)?;
+    let (s, (elements, _)) = items_until(fd_extension_element, brc_close)(s)?;
+    Ok((
+        s,
+        FdExtensionElement {
+            tag: tag.to_string(),
+            name: name.map(|n| n.to_string()),
+            properties,
+            elements,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// e.g. `attribute speed { SomeIpGetterID = 1 }`
+pub fn fd_attribute(s: Input) -> IResult<Input, FdAttribute> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("attribute")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, target) = identifier(s)?;
+    let (s, (properties, overwrites)) = typed_body(s)?;
+    Ok((
+        s,
+        FdAttribute {
+            target: target.to_string(),
+            properties,
+            overwrites,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// e.g. `method sayHello { SomeIpMethodID = 1 in { name { ... } } out { ... } }`
+pub fn fd_method(s: Input) -> IResult<Input, FdMethod> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("method")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, target) = recognize(pair(identifier, opt(selector)))(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, properties) = fd_property_set(s)?;
+    let (s, r#in) = opt(element("in args", fd_arguments("in")))(s)?;
+    let (s, out) = opt(element("out args", fd_arguments("out")))(s)?;
+    let (s, _) = brc_close(s)?;
+    Ok((
+        s,
+        FdMethod {
+            target: target.to_string(),
+            properties,
+            r#in,
+            out,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// e.g. `broadcast greeted { SomeIpEventID = 32769 }`
+pub fn fd_broadcast(s: Input) -> IResult<Input, FdBroadcast> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("broadcast")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, target) = recognize(pair(identifier, opt(selector)))(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, properties) = fd_property_set(s)?;
+    let (s, out) = opt(element("out args", fd_arguments("out")))(s)?;
+    let (s, _) = brc_close(s)?;
+    Ok((
+        s,
+        FdBroadcast {
+            target: target.to_string(),
+            properties,
+            out,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// Argument deployments introduced by `direction`, i.e. `in` or `out`.
+fn fd_arguments<'a>(
+    direction: &'static str,
+) -> impl FnMut(Input<'a>) -> IResult<Input<'a>, FdArgumentList> {
+    move |s: Input<'a>| {
+        let (s, _) = sp(s)?;
+        let (s, _) = keyword(direction)(s)?;
+        let (s, _) = brc_open(s)?;
+        let (s, (arguments, _)) = items_until(fd_argument, brc_close)(s)?;
+        Ok((s, FdArgumentList { arguments }))
+    }
+}
+
+/// e.g. `name { SomeIpStringEncoding = utf16le }`
+pub fn fd_argument(s: Input) -> IResult<Input, FdArgument> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, target) = identifier(s)?;
+    let (s, (properties, overwrites)) = typed_body(s)?;
+    Ok((
+        s,
+        FdArgument {
+            target: target.to_string(),
+            properties,
+            overwrites,
+            span: span(start, s),
+        },
+    ))
+}
+
+pub fn fd_type_definition(s: Input) -> IResult<Input, FdTypeDefinition> {
+    alt((
+        map(element("array", fd_array), FdTypeDefinition::Array),
+        map(element("struct", fd_struct), |s| {
+            FdTypeDefinition::Compound(FdCompound::Struct(s))
+        }),
+        map(element("union", fd_union), |u| {
+            FdTypeDefinition::Compound(FdCompound::Union(u))
+        }),
+        map(
+            element("enumeration", fd_enumeration),
+            FdTypeDefinition::Enumeration,
+        ),
+        map(element("typedef", fd_type_def), FdTypeDefinition::TypeDef),
+        map(element("map", fd_map), FdTypeDefinition::Map),
+    ))(s)
+}
+
+pub fn fd_array(s: Input) -> IResult<Input, FdArray> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("array")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, target) = identifier(s)?;
+    let (s, (properties, overwrites)) = typed_body(s)?;
+    Ok((
+        s,
+        FdArray {
+            target: target.to_string(),
+            properties,
+            overwrites,
+            span: span(start, s),
+        },
+    ))
+}
+
+pub fn fd_struct(s: Input) -> IResult<Input, FdStruct> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("struct")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, target) = identifier(s)?;
+    let (s, (properties, fields)) = fields_body(s)?;
+    Ok((
+        s,
+        FdStruct {
+            target: target.to_string(),
+            properties,
+            fields,
+            span: span(start, s),
+        },
+    ))
+}
+
+pub fn fd_union(s: Input) -> IResult<Input, FdUnion> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("union")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, target) = identifier(s)?;
+    let (s, (properties, fields)) = fields_body(s)?;
+    Ok((
+        s,
+        FdUnion {
+            target: target.to_string(),
+            properties,
+            fields,
+            span: span(start, s),
+        },
+    ))
+}
+
+pub fn fd_field(s: Input) -> IResult<Input, FdField> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, target) = identifier(s)?;
+    let (s, (properties, overwrites)) = typed_body(s)?;
+    Ok((
+        s,
+        FdField {
+            target: target.to_string(),
+            properties,
+            overwrites,
+            span: span(start, s),
+        },
+    ))
+}
+
+pub fn fd_enumeration(s: Input) -> IResult<Input, FdEnumeration> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("enumeration")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, target) = identifier(s)?;
+    let (s, (properties, enumerators)) = enumerators_body(s)?;
+    Ok((
+        s,
+        FdEnumeration {
+            target: target.to_string(),
+            properties,
+            enumerators,
+            span: span(start, s),
+        },
+    ))
+}
+
+pub fn fd_enum_value(s: Input) -> IResult<Input, FdEnumValue> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, target) = identifier(s)?;
+    let (s, properties) = properties_body(s)?;
+    Ok((
+        s,
+        FdEnumValue {
+            target: target.to_string(),
+            properties,
+            span: span(start, s),
+        },
+    ))
+}
+
+pub fn fd_type_def(s: Input) -> IResult<Input, FdTypeDef> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("typedef")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, target) = identifier(s)?;
+    let (s, properties) = properties_body(s)?;
+    Ok((
+        s,
+        FdTypeDef {
+            target: target.to_string(),
+            properties,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// e.g. `map Settings { key { ... } value { ... } }`
+pub fn fd_map(s: Input) -> IResult<Input, FdMap> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("map")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, target) = identifier(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, properties) = fd_property_set(s)?;
+    let (s, key) = opt(preceded(pair(sp, keyword("key")), properties_body))(s)?;
+    let (s, value) = opt(preceded(pair(sp, keyword("value")), properties_body))(s)?;
+    let (s, _) = brc_close(s)?;
+    Ok((
+        s,
+        FdMap {
+            target: target.to_string(),
+            properties,
+            key: key.map(|properties| FdMapKey { properties }),
+            value: value.map(|properties| FdMapValue { properties }),
+            span: span(start, s),
+        },
+    ))
+}
+
+/// e.g. `#{ ... }`, `#struct { ... }`, `#union { ... }` or `#enumeration { ... }`
+pub fn fd_type_overwrites(s: Input) -> IResult<Input, FdTypeOverwrites> {
+    let (s, _) = preceded(sp, char('#'))(s)?;
+    cut(alt((
+        map(
+            preceded(pair(sp, keyword("struct")), fields_body),
+            |(properties, fields)| {
+                FdTypeOverwrites::Struct(FdStructOverwrites { properties, fields })
+            },
+        ),
+        map(
+            preceded(pair(sp, keyword("union")), fields_body),
+            |(properties, fields)| {
+                FdTypeOverwrites::Union(FdUnionOverwrites { properties, fields })
+            },
+        ),
+        map(
+            preceded(pair(sp, keyword("enumeration")), enumerators_body),
+            |(properties, enumerators)| {
+                FdTypeOverwrites::Enumeration(FdEnumerationOverwrites {
+                    properties,
+                    enumerators,
+                })
+            },
+        ),
+        map(properties_body, |properties| {
+            FdTypeOverwrites::Plain(FdPlainTypeOverwrites { properties })
+        }),
+    )))(s)
+}
+
+/// `{ properties }`
+fn properties_body(s: Input) -> IResult<Input, FdPropertySet> {
+    delimited(brc_open, fd_property_set, brc_close)(s)
+}
+
+/// `{ properties overwrites }` of elements having a type.
+fn typed_body(s: Input) -> IResult<Input, (FdPropertySet, Option<FdTypeOverwrites>)> {
+    let (s, _) = brc_open(s)?;
+    let (s, properties) = fd_property_set(s)?;
+    let (s, overwrites) = opt(fd_type_overwrites)(s)?;
+    let (s, _) = brc_close(s)?;
+    Ok((s, (properties, overwrites)))
+}
+
+/// `{ properties fields }` of structs and unions.
+fn fields_body(s: Input) -> IResult<Input, (FdPropertySet, Vec<FdField>)> {
+    let (s, _) = brc_open(s)?;
+    let (s, properties) = fd_property_set(s)?;
+    let (s, (fields, _)) = items_until(fd_field, brc_close)(s)?;
+    Ok((s, (properties, fields)))
+}
+
+/// `{ properties enumerators }` of enumerations.
+fn enumerators_body(s: Input) -> IResult<Input, (FdPropertySet, Vec<FdEnumValue>)> {
+    let (s, _) = brc_open(s)?;
+    let (s, properties) = fd_property_set(s)?;
+    let (s, (enumerators, _)) = items_until(fd_enum_value, brc_close)(s)?;
+    Ok((s, (properties, enumerators)))
+}
+
+pub fn fd_property_set(s: Input) -> IResult<Input, FdPropertySet> {
+    map(many0(fd_property), |items| FdPropertySet { items })(s)
+}
+
+/// e.g. `SomeIpServiceID = 4660`
+pub fn fd_property(s: Input) -> IResult<Input, FdProperty> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, name) = terminated(identifier, pair(sp, char('=')))(s)?;
+    let (s, _) = sp(s)?;
+    let (s, value) = cut(fd_value)(s)?;
+    Ok((
+        s,
+        FdProperty {
+            name: name.to_string(),
+            value,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// Single value or array of values, e.g. `{ 1, 2 }`
+pub fn fd_value(s: Input) -> IResult<Input, FdValue> {
+    alt((
+        map(
+            delimited(
+                char('{'),
+                separated_list0(pair(sp, char(',')), preceded(sp, fd_single_value)),
+                brc_close,
+            ),
+            FdValue::Array,
+        ),
+        fd_single_value,
+    ))(s)
+}
+
+/// String, boolean, integer or a possibly qualified name.
+fn fd_single_value(s: Input) -> IResult<Input, FdValue> {
+    expect(
+        "value",
+        alt((
+            map(string_literal, FdValue::String),
+            value(FdValue::Boolean(true), keyword("true")),
+            value(FdValue::Boolean(false), keyword("false")),
+            map_res(pair(opt(char('-')), integer), |(sign, i)| {
+                i64::try_from(if sign.is_some() { -i } else { i }).map(FdValue::Integer)
+            }),
+            map(fqn, |name| FdValue::Generic(name.to_string())),
+        )),
+    )(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEPLOYMENT: &str = r#"package org.example.deployment
+
+import "org.example.spec.fdepl"
+import org.example.spec
+
+specification org.example.spec extends org.example.base {
+    for interfaces {
+        ServiceID : Integer ;
+        Name : String (default: "unnamed");
+    }
+}
+
+define org.example.spec for interface org.example.HelloWorld as HelloWorldDeployment {
+    ServiceID = 0x1234
+
+    attribute speed {
+        GetterID = 1
+        #{ BitWidth = 8 }
+    }
+
+    method sayHello:v2 {
+        MethodID = 2
+        in {
+            name { Encoding = utf16le }
+        }
+        out {
+            value_a { }
+        }
+    }
+
+    broadcast greeted {
+        EventID = -1
+        Reliable = true
+    }
+
+    struct Point {
+        x { BitWidth = 16 }
+    }
+
+    enumeration Gear {
+        Backing = UInt8
+        Park { Value = 0 }
+    }
+
+    map Settings {
+        key { Encoding = utf8 }
+    }
+}
+
+define org.example.spec for typeCollection {
+    typedef Timestamp { Unit = "ms" }
+}
+
+define org.example.spec for provider as Service {
+    instance org.example.HelloWorld as Primary {
+        InstanceID = "primary"
+    }
+}
+
+define org.example.spec for device Gateway {
+    Address = "10.0.0.1"
+    port Diagnostics {
+        Port = 13400
+    }
+}
+"#;
+
+    #[test]
+    pub fn fd_model_test() {
+        let model = parse_fdepl(DEPLOYMENT).unwrap();
+        assert_eq!(model.name, "org.example.deployment");
+        assert_eq!(
+            model.imports,
+            [
+                Import::ImportUri("org.example.spec.fdepl".to_string()),
+                Import::ImportedSpec("org.example.spec".to_string()),
+            ]
+        );
+        assert_eq!(model.specifications.len(), 1);
+        assert_eq!(
+            model.specifications[0].base.as_deref(),
+            Some("org.example.base")
+        );
+        assert_eq!(model.deployments.len(), 4);
+
+        let interface = match &model.deployments[0] {
+            FdRootElement::Interface(interface) => interface,
+            other => panic!("unexpected deployment {:?}", other),
+        };
+        assert_eq!(interface.spec, "org.example.spec");
+        assert_eq!(interface.target, "org.example.HelloWorld");
+        assert_eq!(interface.name.as_deref(), Some("HelloWorldDeployment"));
+        assert_eq!(
+            interface.properties.get("ServiceID").map(|p| &p.value),
+            Some(&FdValue::Integer(0x1234))
+        );
+        assert_eq!(interface.attributes.len(), 1);
+        assert!(matches!(
+            interface.attributes[0].overwrites,
+            Some(FdTypeOverwrites::Plain(_))
+        ));
+
+        let method = &interface.methods[0];
+        assert_eq!(method.target, "sayHello:v2");
+        let argument = &method.r#in.as_ref().unwrap().arguments[0];
+        assert_eq!(argument.target, "name");
+        assert_eq!(
+            argument.properties.get("Encoding").map(|p| &p.value),
+            Some(&FdValue::Generic("utf16le".to_string()))
+        );
+        assert_eq!(method.out.as_ref().unwrap().arguments.len(), 1);
+
+        let broadcast = &interface.broadcasts[0];
+        let properties: Vec<_> = broadcast
+            .properties
+            .items
+            .iter()
+            .map(|p| (p.name.as_str(), &p.value))
+            .collect();
+        assert_eq!(
+            properties,
+            [
+                ("EventID", &FdValue::Integer(-1)),
+                ("Reliable", &FdValue::Boolean(true)),
+            ]
+        );
+        assert_eq!(interface.types.len(), 3);
+
+        match &model.deployments[1] {
+            FdRootElement::TypeCollection(types) => {
+                assert_eq!(types.target, None);
+                assert!(matches!(types.types[0], FdTypeDefinition::TypeDef(_)));
+            }
+            other => panic!("unexpected deployment {:?}", other),
+        }
+        match &model.deployments[2] {
+            FdRootElement::Provider(provider) => {
+                assert_eq!(provider.name.as_deref(), Some("Service"));
+                assert_eq!(provider.instances[0].target, "org.example.HelloWorld");
+            }
+            other => panic!("unexpected deployment {:?}", other),
+        }
+        match &model.deployments[3] {
+            FdRootElement::Extension(extension) => {
+                assert_eq!(extension.tag, "device");
+                assert_eq!(extension.name.as_deref(), Some("Gateway"));
+                assert_eq!(extension.elements[0].tag, "port");
+            }
+            other => panic!("unexpected deployment {:?}", other),
+        }
+    }
+
+    #[test]
+    pub fn fd_specification_test() {
+        let def = "specification org.example.spec extends org.example.base {
+    for strings {
+        Encoding : { utf8, utf16be utf16le } (default: utf8);
+    }
+    for providers {
+        Clients : Interface[] (optional, default: org.example.Client)
+    }
+}";
+
+        let (_, specification) = fd_specification(def.into()).unwrap();
+        assert_eq!(specification.name, "org.example.spec");
+        assert_eq!(specification.base.as_deref(), Some("org.example.base"));
+        let hosts: Vec<_> = specification.declarations.iter().map(|d| &d.host).collect();
+        assert_eq!(
+            hosts,
+            [&FdPropertyHost::Strings, &FdPropertyHost::Providers]
+        );
+
+        let encoding = &specification.declarations[0].properties[0];
+        assert_eq!(encoding.name, "Encoding");
+        assert_eq!(encoding.r#type.predefined, None);
+        assert_eq!(
+            encoding.r#type.complex,
+            Some(FdEnumType {
+                enumerators: vec![
+                    "utf8".to_string(),
+                    "utf16be".to_string(),
+                    "utf16le".to_string(),
+                ],
+            })
+        );
+        assert_eq!(encoding.optional, None);
+        assert_eq!(encoding.default, Some(FdValue::Generic("utf8".to_string())));
+
+        let clients = &specification.declarations[1].properties[0];
+        assert_eq!(clients.name, "Clients");
+        assert_eq!(
+            clients.r#type.predefined,
+            Some(FdPredefinedTypeId::Interface)
+        );
+        assert!(clients.r#type.array);
+        assert_eq!(clients.optional, Some(true));
+        assert_eq!(
+            clients.default,
+            Some(FdValue::Generic("org.example.Client".to_string()))
+        );
+    }
+
+    #[test]
+    pub fn fd_property_decl_flags_test() {
+        let flags = |def: &str| {
+            let (_, decl) = fd_property_decl(def.into()).unwrap();
+            (decl.optional, decl.default)
+        };
+
+        assert_eq!(
+            flags("Timeout : Integer (optional, default: 100);"),
+            (Some(true), Some(FdValue::Integer(100)))
+        );
+        assert_eq!(
+            flags("Timeout : Integer ( default: 100 , optional )"),
+            (Some(true), Some(FdValue::Integer(100)))
+        );
+        assert_eq!(
+            flags("Groups : Integer[] (optional, default: {1, 2});"),
+            (
+                Some(true),
+                Some(FdValue::Array(vec![
+                    FdValue::Integer(1),
+                    FdValue::Integer(2)
+                ]))
+            )
+        );
+        assert_eq!(flags("Reliable : Boolean (default: false);").0, None);
+        assert_eq!(flags("Groups : Integer[];"), (None, None));
+    }
+
+    #[test]
+    pub fn fd_model_error_test() {
+        let def = "package org.example.deployment
+
+define org.example.spec for interface org.example.HelloWorld {
+    method sayHello {
+        MethodID = }
+    }
+}
+";
+        let error = parse_fdepl(def).unwrap_err();
+        assert_eq!((error.line, error.column), (5, 20));
+        assert_eq!(error.expected, ["`{`", "value"]);
+        assert_eq!(error.context, ["interface deployment", "method sayHello"]);
+    }
+}
@@ -0,0 +1,162 @@
+use super::{FdModel, FdPropertyDecl, FdPropertyHost, FdSpecification};
+use crate::resolve::hierarchy;
+
+impl FdModel {
+    /// Looks up a specification by its name, optionally qualified by the package name.
+    pub fn find_specification(&self, name: &str) -> Option<&FdSpecification> {
+        let local_name = name
+            .strip_prefix(self.name.as_str())
+            .and_then(|n| n.strip_prefix('.'));
+        self.specifications
+            .iter()
+            .find(|s| s.name == name || Some(s.name.as_str()) == local_name)
+    }
+
+    /// Chain of extended specifications starting with the root base specification
+    /// and ending with the specification itself.
+    /// Unresolvable bases end the chain, cyclic extension is visited only once.
+    pub fn specification_hierarchy<'a>(
+        &'a self,
+        specification: &'a FdSpecification,
+    ) -> Vec<&'a FdSpecification> {
+        hierarchy(specification, |s| {
+            s.base
+                .as_deref()
+                .and_then(|base| self.find_specification(base))
+        })
+    }
+
+    /// Properties declared for `host` by a specification including the inherited ones,
+    /// base declarations first. Redeclared properties replace the inherited declaration.
+    pub fn property_declarations<'a>(
+        &'a self,
+        specification: &'a FdSpecification,
+        host: &FdPropertyHost,
+    ) -> Vec<&'a FdPropertyDecl> {
+        let mut declarations: Vec<&FdPropertyDecl> = Vec::new();
+        for specification in self.specification_hierarchy(specification) {
+            let properties = specification
+                .declarations
+                .iter()
+                .filter(|d| &d.host == host)
+                .flat_map(|d| d.properties.iter());
+            for property in properties {
+                match declarations.iter_mut().find(|d| d.name == property.name) {
+                    Some(declaration) => *declaration = property,
+                    None => declarations.push(property),
+                }
+            }
+        }
+        declarations
+    }
+
+    /// Declaration of the property `name` for `host` in a specification
+    /// or the specifications it extends.
+    pub fn find_property_declaration<'a>(
+        &'a self,
+        specification: &'a FdSpecification,
+        host: &FdPropertyHost,
+        name: &str,
+    ) -> Option<&'a FdPropertyDecl> {
+        self.property_declarations(specification, host)
+            .into_iter()
+            .find(|d| d.name == name)
+    }
+
+    /// Hosts the property `name` is declared for by a specification including
+    /// the specifications it extends, base declarations first.
+    pub fn property_hosts<'a>(
+        &'a self,
+        specification: &'a FdSpecification,
+        name: &str,
+    ) -> Vec<&'a FdPropertyHost> {
+        let mut hosts: Vec<&FdPropertyHost> = Vec::new();
+        let declarations = self
+            .specification_hierarchy(specification)
+            .into_iter()
+            .flat_map(|s| s.declarations.iter());
+        for declaration in declarations {
+            if declaration.properties.iter().any(|p| p.name == name)
+                && !hosts.contains(&&declaration.host)
+            {
+                hosts.push(&declaration.host);
+            }
+        }
+        hosts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deploy::{FdPropertyHost, FdValue};
+    use crate::parse_fdepl;
+
+    const SPECIFICATIONS: &str = r#"package org.example
+
+specification base {
+    for interfaces {
+        ServiceID : Integer;
+        Reliable : Boolean (default: false);
+    }
+    for methods {
+        MethodID : Integer;
+    }
+}
+
+specification org.example.someip extends base {
+    for interfaces {
+        Reliable : Boolean (default: true);
+        EventGroups : Integer[] (optional);
+    }
+}
+
+specification cyclic extends org.example.loop {
+}
+
+specification loop extends cyclic {
+}
+"#;
+
+    #[test]
+    pub fn property_declarations_test() {
+        let model = parse_fdepl(SPECIFICATIONS).unwrap();
+        let someip = model.find_specification("org.example.someip").unwrap();
+        assert_eq!(someip.base.as_deref(), Some("base"));
+
+        let hierarchy: Vec<_> = model
+            .specification_hierarchy(someip)
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(hierarchy, ["base", "org.example.someip"]);
+
+        let declarations = model.property_declarations(someip, &FdPropertyHost::Interfaces);
+        let names: Vec<_> = declarations.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["ServiceID", "Reliable", "EventGroups"]);
+        assert!(declarations[0].is_mandatory());
+        assert_eq!(declarations[1].default, Some(FdValue::Boolean(true)));
+        assert!(!declarations[2].is_mandatory());
+
+        let methods = model.property_declarations(someip, &FdPropertyHost::Methods);
+        assert_eq!(methods[0].name, "MethodID");
+
+        let reliable = model
+            .find_property_declaration(someip, &FdPropertyHost::Interfaces, "Reliable")
+            .unwrap();
+        assert_eq!(reliable.default, Some(FdValue::Boolean(true)));
+        assert!(model
+            .find_property_declaration(someip, &FdPropertyHost::Methods, "Reliable")
+            .is_none());
+        assert!(model
+            .find_property_declaration(someip, &FdPropertyHost::Interfaces, "Missing")
+            .is_none());
+        assert_eq!(
+            model.property_hosts(someip, "Reliable"),
+            [&FdPropertyHost::Interfaces]
+        );
+        assert!(model.property_hosts(someip, "Missing").is_empty());
+
+        let cyclic = model.find_specification("cyclic").unwrap();
+        assert_eq!(model.specification_hierarchy(cyclic).len(), 2);
+    }
+}
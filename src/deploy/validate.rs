@@ -0,0 +1,582 @@
+//! Checks of deployments against their specification, similar to the
+//! validation of the Eclipse FDeploy editor.
+//!
+//! Properties of the types of attributes, arguments, fields, arrays and typedefs,
+//! e.g. those declared `for strings`, are accepted on these elements but are not
+//! required, as that depends on the Franca IDL type of the element.
+
+use std::fmt;
+
+use super::{
+    FdArgumentList, FdCompound, FdEnumValue, FdField, FdModel, FdPropertyHost, FdPropertySet,
+    FdRootElement, FdSpecification, FdTypeDefinition, FdTypeOverwrites, FdValue,
+};
+use crate::deploy::value::FdValueError;
+use crate::Span;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub kind: ValidationErrorKind,
+    /// Property or, for missing properties, the element lacking it
+    pub span: Span,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.span.line, self.span.column, self.kind)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationErrorKind {
+    /// Deployment refers to a specification that does not exist.
+    UnknownSpecification(String),
+    /// Mandatory property of the element is not set.
+    MissingProperty(String),
+    /// Property is not declared by the specification.
+    UnknownProperty(String),
+    /// Property is declared for other kinds of elements only, listed in `hosts`.
+    WrongHost {
+        property: String,
+        hosts: Vec<FdPropertyHost>,
+    },
+    /// Value does not match the type of the property.
+    InvalidValue {
+        property: String,
+        error: FdValueError,
+    },
+    /// Integer does not fit the 32 bit `Integer` type of FDeploy.
+    OutOfRange { property: String, value: i64 },
+}
+
+impl fmt::Display for ValidationErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationErrorKind::UnknownSpecification(name) => {
+                write!(f, "unknown specification `{}`", name)
+            }
+            ValidationErrorKind::MissingProperty(name) => {
+                write!(f, "missing mandatory property `{}`", name)
+            }
+            ValidationErrorKind::UnknownProperty(name) => write!(f, "unknown property `{}`", name),
+            ValidationErrorKind::WrongHost { property, hosts } => {
+                let hosts: Vec<_> = hosts.iter().map(FdPropertyHost::name).collect();
+                write!(
+                    f,
+                    "property `{}` is declared for {} only",
+                    property,
+                    hosts.join(", ")
+                )
+            }
+            ValidationErrorKind::InvalidValue { property, error } => {
+                write!(f, "invalid value of `{}`: {}", property, error)
+            }
+            ValidationErrorKind::OutOfRange { property, value } => {
+                write!(f, "value {} of `{}` is out of range", value, property)
+            }
+        }
+    }
+}
+
+/// Hosts of properties depending on the type of an element.
+const TYPE_HOSTS: &[FdPropertyHost] = &[
+    FdPropertyHost::Strings,
+    FdPropertyHost::Numbers,
+    FdPropertyHost::Integers,
+    FdPropertyHost::Floats,
+    FdPropertyHost::Booleans,
+    FdPropertyHost::ByteBuffers,
+    FdPropertyHost::Enumerations,
+    FdPropertyHost::Arrays,
+];
+
+impl FdModel {
+    /// Checks all deployments of this model against their specifications,
+    /// which are looked up in `specifications`, e.g. the model itself or an imported one.
+    pub fn validate(&self, specifications: &FdModel) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        for deployment in &self.deployments {
+            match specifications.find_specification(deployment.spec()) {
+                Some(specification) => {
+                    let mut validator = Validator {
+                        specifications,
+                        specification,
+                        errors: &mut errors,
+                    };
+                    validator.root_element(deployment);
+                }
+                None => errors.push(ValidationError {
+                    kind: ValidationErrorKind::UnknownSpecification(deployment.spec().to_string()),
+                    span: deployment.span(),
+                }),
+            }
+        }
+        errors
+    }
+}
+
+struct Validator<'a> {
+    specifications: &'a FdModel,
+    specification: &'a FdSpecification,
+    errors: &'a mut Vec<ValidationError>,
+}
+
+impl<'a> Validator<'a> {
+    fn root_element(&mut self, deployment: &FdRootElement) {
+        match deployment {
+            FdRootElement::Interface(interface) => {
+                self.element(
+                    &interface.properties,
+                    FdPropertyHost::Interfaces,
+                    interface.span,
+                );
+                for attribute in &interface.attributes {
+                    self.typed_element(
+                        &attribute.properties,
+                        FdPropertyHost::Attributes,
+                        attribute.span,
+                    );
+                    self.overwrites(attribute.overwrites.as_ref());
+                }
+                for method in &interface.methods {
+                    self.element(&method.properties, FdPropertyHost::Methods, method.span);
+                    self.arguments(method.r#in.as_ref());
+                    self.arguments(method.out.as_ref());
+                }
+                for broadcast in &interface.broadcasts {
+                    self.element(
+                        &broadcast.properties,
+                        FdPropertyHost::Broadcasts,
+                        broadcast.span,
+                    );
+                    self.arguments(broadcast.out.as_ref());
+                }
+                for r#type in &interface.types {
+                    self.type_definition(r#type);
+                }
+            }
+            FdRootElement::TypeCollection(types) => {
+                self.element(
+                    &types.properties,
+                    FdPropertyHost::TypeCollections,
+                    types.span,
+                );
+                for r#type in &types.types {
+                    self.type_definition(r#type);
+                }
+            }
+            FdRootElement::Provider(provider) => {
+                self.element(
+                    &provider.properties,
+                    FdPropertyHost::Providers,
+                    provider.span,
+                );
+                for instance in &provider.instances {
+                    self.element(
+                        &instance.properties,
+                        FdPropertyHost::Instances,
+                        instance.span,
+                    );
+                }
+            }
+            FdRootElement::Extension(extension) => {
+                // hosts of extensions are not known, only values are checked
+                self.properties(&extension.properties, None);
+                let mut elements: Vec<_> = extension.elements.iter().collect();
+                while let Some(element) = elements.pop() {
+                    self.properties(&element.properties, None);
+                    elements.extend(&element.elements);
+                }
+            }
+        }
+    }
+
+    fn type_definition(&mut self, r#type: &FdTypeDefinition) {
+        match r#type {
+            FdTypeDefinition::Array(array) => {
+                self.typed_element(&array.properties, FdPropertyHost::Arrays, array.span);
+                self.overwrites(array.overwrites.as_ref());
+            }
+            FdTypeDefinition::Compound(FdCompound::Struct(r#struct)) => {
+                self.element(&r#struct.properties, FdPropertyHost::Structs, r#struct.span);
+                self.fields(&r#struct.fields, FdPropertyHost::StructFields);
+            }
+            FdTypeDefinition::Compound(FdCompound::Union(union)) => {
+                self.element(&union.properties, FdPropertyHost::Unions, union.span);
+                self.fields(&union.fields, FdPropertyHost::UnionFields);
+            }
+            FdTypeDefinition::Enumeration(enumeration) => {
+                self.element(
+                    &enumeration.properties,
+                    FdPropertyHost::Enumerations,
+                    enumeration.span,
+                );
+                self.enumerators(&enumeration.enumerators);
+            }
+            FdTypeDefinition::TypeDef(type_def) => {
+                self.typed_element(
+                    &type_def.properties,
+                    FdPropertyHost::TypeDefs,
+                    type_def.span,
+                );
+            }
+            FdTypeDefinition::Map(map) => {
+                self.element(&map.properties, FdPropertyHost::Maps, map.span);
+                if let Some(key) = &map.key {
+                    self.typed_element(&key.properties, FdPropertyHost::MapKeys, map.span);
+                }
+                if let Some(value) = &map.value {
+                    self.typed_element(&value.properties, FdPropertyHost::MapValues, map.span);
+                }
+            }
+        }
+    }
+
+    fn arguments(&mut self, arguments: Option<&FdArgumentList>) {
+        for argument in arguments.iter().flat_map(|a| a.arguments.iter()) {
+            self.typed_element(
+                &argument.properties,
+                FdPropertyHost::Arguments,
+                argument.span,
+            );
+            self.overwrites(argument.overwrites.as_ref());
+        }
+    }
+
+    fn fields(&mut self, fields: &[FdField], host: FdPropertyHost) {
+        for field in fields {
+            self.typed_element(&field.properties, host.clone(), field.span);
+            self.overwrites(field.overwrites.as_ref());
+        }
+    }
+
+    fn enumerators(&mut self, enumerators: &[FdEnumValue]) {
+        for enumerator in enumerators {
+            self.element(
+                &enumerator.properties,
+                FdPropertyHost::Enumerators,
+                enumerator.span,
+            );
+        }
+    }
+
+    /// Overwrites only change properties of the type deployment, none are mandatory.
+    fn overwrites(&mut self, overwrites: Option<&FdTypeOverwrites>) {
+        let overwrites = match overwrites {
+            Some(overwrites) => overwrites,
+            None => return,
+        };
+        match overwrites {
+            FdTypeOverwrites::Plain(plain) => self.properties(&plain.properties, Some(TYPE_HOSTS)),
+            FdTypeOverwrites::Struct(r#struct) => {
+                self.properties(&r#struct.properties, Some(&[FdPropertyHost::Structs]));
+                self.fields(&r#struct.fields, FdPropertyHost::StructFields);
+            }
+            FdTypeOverwrites::Union(union) => {
+                self.properties(&union.properties, Some(&[FdPropertyHost::Unions]));
+                self.fields(&union.fields, FdPropertyHost::UnionFields);
+            }
+            FdTypeOverwrites::Enumeration(enumeration) => {
+                self.properties(
+                    &enumeration.properties,
+                    Some(&[FdPropertyHost::Enumerations]),
+                );
+                self.enumerators(&enumeration.enumerators);
+            }
+        }
+    }
+
+    /// Element of the kind `host`, all mandatory properties for `host` have to be set.
+    fn element(&mut self, properties: &FdPropertySet, host: FdPropertyHost, span: Span) {
+        self.missing_properties(properties, &host, span);
+        self.properties(properties, Some(&[host]));
+    }
+
+    /// Element of the kind `host` that also accepts properties of its type.
+    fn typed_element(&mut self, properties: &FdPropertySet, host: FdPropertyHost, span: Span) {
+        self.missing_properties(properties, &host, span);
+        let mut hosts = vec![host];
+        hosts.extend_from_slice(TYPE_HOSTS);
+        self.properties(properties, Some(&hosts));
+    }
+
+    fn missing_properties(
+        &mut self,
+        properties: &FdPropertySet,
+        host: &FdPropertyHost,
+        span: Span,
+    ) {
+        let declarations = self
+            .specifications
+            .property_declarations(self.specification, host);
+        for declaration in declarations {
+            if declaration.is_mandatory() && properties.get(&declaration.name).is_none() {
+                self.errors.push(ValidationError {
+                    kind: ValidationErrorKind::MissingProperty(declaration.name.clone()),
+                    span,
+                });
+            }
+        }
+    }
+
+    /// Checks the properties are declared for one of `hosts`, any if `None`,
+    /// and their values match one of these declarations.
+    fn properties(&mut self, properties: &FdPropertySet, hosts: Option<&[FdPropertyHost]>) {
+        for property in &properties.items {
+            let declared = self
+                .specifications
+                .property_hosts(self.specification, &property.name);
+            let hosts = match hosts {
+                Some(hosts) => hosts.iter().collect(),
+                None => declared.clone(),
+            };
+            let values: Vec<_> = hosts
+                .into_iter()
+                .filter_map(|host| {
+                    self.specifications.find_property_declaration(
+                        self.specification,
+                        host,
+                        &property.name,
+                    )
+                })
+                .map(|declaration| declaration.typed_value(&property.value))
+                .collect();
+
+            let kind = match values.iter().find(|v| v.is_ok()).or(values.first()) {
+                Some(Ok(value)) => match out_of_range(value) {
+                    Some(value) => ValidationErrorKind::OutOfRange {
+                        property: property.name.clone(),
+                        value,
+                    },
+                    None => continue,
+                },
+                Some(Err(error)) => ValidationErrorKind::InvalidValue {
+                    property: property.name.clone(),
+                    error: error.clone(),
+                },
+                None if declared.is_empty() => {
+                    ValidationErrorKind::UnknownProperty(property.name.clone())
+                }
+                None => ValidationErrorKind::WrongHost {
+                    property: property.name.clone(),
+                    hosts: declared.into_iter().cloned().collect(),
+                },
+            };
+            self.errors.push(ValidationError {
+                kind,
+                span: property.span,
+            });
+        }
+    }
+}
+
+/// First integer of `value` not representable as 32 bit integer.
+fn out_of_range(value: &FdValue) -> Option<i64> {
+    match value {
+        FdValue::Integer(i) if i32::try_from(*i).is_err() => Some(*i),
+        FdValue::Array(values) => values.iter().find_map(out_of_range),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValidationErrorKind;
+    use crate::deploy::value::FdValueError;
+    use crate::deploy::FdPropertyHost;
+    use crate::parse_fdepl;
+
+    const SPECIFICATION: &str = r#"package org.example
+
+specification someip {
+    for interfaces {
+        SomeIpServiceID : Integer;
+    }
+    for methods {
+        SomeIpMethodID : Integer;
+        SomeIpReliable : Boolean (default: false);
+    }
+    for arguments {
+        SomeIpArgumentName : String (optional);
+    }
+    for strings {
+        SomeIpStringEncoding : {utf8, utf16be, utf16le} (default: utf8);
+    }
+}
+"#;
+
+    const DEPLOYMENT: &str = r#"package org.example.deployment
+
+define org.example.someip for interface org.example.HelloWorld {
+    SomeIpServiceID = 0x1234
+
+    method sayHello {
+        SomeIpMethodID = "one"
+        in {
+            name {
+                SomeIpStringEncoding = utf32
+                SomeIpArgumentName = "name"
+            }
+        }
+    }
+
+    method sayBye {
+        SomeIpMethodID = 0x100000000
+        SomeIpServiceID = 1
+    }
+}
+
+define org.example.someip for interface org.example.Other {
+    SomeIpInstanceID = 1
+}
+
+define org.example.missing for interface org.example.Third {
+}
+"#;
+
+    #[test]
+    pub fn validate_test() {
+        let specifications = parse_fdepl(SPECIFICATION).unwrap();
+        let model = parse_fdepl(DEPLOYMENT).unwrap();
+        assert!(specifications.validate(&specifications).is_empty());
+
+        let errors = model.validate(&specifications);
+        let errors: Vec<_> = errors
+            .iter()
+            .map(|e| (e.span.line, e.span.column, e.kind.clone()))
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                (
+                    7,
+                    9,
+                    ValidationErrorKind::InvalidValue {
+                        property: "SomeIpMethodID".to_string(),
+                        error: FdValueError::TypeMismatch(
+                            specifications.specifications[0].declarations[1].properties[0]
+                                .r#type
+                                .clone()
+                        ),
+                    }
+                ),
+                (
+                    10,
+                    17,
+                    ValidationErrorKind::InvalidValue {
+                        property: "SomeIpStringEncoding".to_string(),
+                        error: FdValueError::UnknownEnumerator("utf32".to_string()),
+                    }
+                ),
+                (
+                    17,
+                    9,
+                    ValidationErrorKind::OutOfRange {
+                        property: "SomeIpMethodID".to_string(),
+                        value: 0x100000000,
+                    }
+                ),
+                (
+                    18,
+                    9,
+                    ValidationErrorKind::WrongHost {
+                        property: "SomeIpServiceID".to_string(),
+                        hosts: vec![FdPropertyHost::Interfaces],
+                    }
+                ),
+                (
+                    22,
+                    1,
+                    ValidationErrorKind::MissingProperty("SomeIpServiceID".to_string())
+                ),
+                (
+                    23,
+                    5,
+                    ValidationErrorKind::UnknownProperty("SomeIpInstanceID".to_string())
+                ),
+                (
+                    26,
+                    1,
+                    ValidationErrorKind::UnknownSpecification("org.example.missing".to_string())
+                ),
+            ]
+        );
+
+        let error = &model.validate(&specifications)[0];
+        assert_eq!(
+            error.to_string(),
+            "7:9: invalid value of `SomeIpMethodID`: value does not match type `Integer`"
+        );
+    }
+
+    const RELIABLE: &str = r#"package org.example
+
+specification reliable {
+    for methods {
+        SomeIpReliable : Boolean (default: false);
+    }
+    for broadcasts {
+        SomeIpReliable : Integer (optional);
+    }
+}
+
+define reliable for interface org.example.HelloWorld {
+    attribute speed {
+        SomeIpReliable = 1
+    }
+    method sayHello {
+        SomeIpReliable = true
+    }
+    broadcast greeted {
+        SomeIpReliable = 1
+    }
+    broadcast left {
+        SomeIpReliable = true
+    }
+}
+
+define reliable for device Gateway {
+    SomeIpReliable = 1
+}
+"#;
+
+    #[test]
+    pub fn validate_host_test() {
+        let model = parse_fdepl(RELIABLE).unwrap();
+        let errors = model.validate(&model);
+        let errors: Vec<_> = errors
+            .iter()
+            .map(|e| (e.span.line, e.span.column, e.kind.clone()))
+            .collect();
+        let integer = model.specifications[0].declarations[1].properties[0]
+            .r#type
+            .clone();
+        assert_eq!(
+            errors,
+            [
+                (
+                    14,
+                    9,
+                    ValidationErrorKind::WrongHost {
+                        property: "SomeIpReliable".to_string(),
+                        hosts: vec![FdPropertyHost::Methods, FdPropertyHost::Broadcasts],
+                    }
+                ),
+                (
+                    23,
+                    9,
+                    ValidationErrorKind::InvalidValue {
+                        property: "SomeIpReliable".to_string(),
+                        error: FdValueError::TypeMismatch(integer),
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            model.validate(&model)[0].kind.to_string(),
+            "property `SomeIpReliable` is declared for methods, broadcasts only"
+        );
+    }
+}
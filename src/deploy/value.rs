@@ -0,0 +1,244 @@
+//! Typing of property values by their declaration.
+
+use std::fmt;
+
+use super::{
+    FdModel, FdPredefinedTypeId, FdProperty, FdPropertyDecl, FdPropertyHost, FdSpecification,
+    FdTypeRef, FdValue,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FdValueError {
+    /// Property is not declared by the specification.
+    UnknownProperty(String),
+    /// Value does not match the declared type.
+    TypeMismatch(FdTypeRef),
+    /// Name is not an enumerator of the declared enumeration type.
+    UnknownEnumerator(String),
+}
+
+impl fmt::Display for FdValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FdValueError::UnknownProperty(name) => write!(f, "unknown property `{}`", name),
+            FdValueError::TypeMismatch(r#type) => {
+                write!(f, "value does not match type `{}`", r#type)
+            }
+            FdValueError::UnknownEnumerator(name) => write!(f, "unknown enumerator `{}`", name),
+        }
+    }
+}
+
+impl std::error::Error for FdValueError {}
+
+impl FdPropertyDecl {
+    /// Checks `value` against the declared type, resolving names to
+    /// enumerators or interface references.
+    pub fn typed_value(&self, value: &FdValue) -> Result<FdValue, FdValueError> {
+        match (self.r#type.array, value) {
+            (true, FdValue::Array(values)) => values
+                .iter()
+                .map(|v| self.typed_element(v))
+                .collect::<Result<_, _>>()
+                .map(FdValue::Array),
+            (false, FdValue::Array(_)) | (true, _) => {
+                Err(FdValueError::TypeMismatch(self.r#type.clone()))
+            }
+            (false, value) => self.typed_element(value),
+        }
+    }
+
+    /// Value typed by the element type of an array type, or by the type itself.
+    fn typed_element(&self, value: &FdValue) -> Result<FdValue, FdValueError> {
+        let mismatch = || FdValueError::TypeMismatch(self.r#type.clone());
+        if let Some(complex) = &self.r#type.complex {
+            return match value {
+                FdValue::Generic(name) | FdValue::Enumerator(name) => {
+                    if complex.enumerators.contains(name) {
+                        Ok(FdValue::Enumerator(name.clone()))
+                    } else {
+                        Err(FdValueError::UnknownEnumerator(name.clone()))
+                    }
+                }
+                _ => Err(mismatch()),
+            };
+        }
+
+        match (self.r#type.predefined.ok_or_else(mismatch)?, value) {
+            (FdPredefinedTypeId::Integer, FdValue::Integer(_))
+            | (FdPredefinedTypeId::String, FdValue::String(_))
+            | (FdPredefinedTypeId::Boolean, FdValue::Boolean(_))
+            | (FdPredefinedTypeId::Interface, FdValue::InterfaceRef(_)) => Ok(value.clone()),
+            (FdPredefinedTypeId::Interface, FdValue::Generic(name)) => {
+                Ok(FdValue::InterfaceRef(name.clone()))
+            }
+            _ => Err(mismatch()),
+        }
+    }
+}
+
+impl FdModel {
+    /// Value of `property` typed by its declaration for `host` in `specification`.
+    pub fn property_value(
+        &self,
+        specification: &FdSpecification,
+        host: &FdPropertyHost,
+        property: &FdProperty,
+    ) -> Result<FdValue, FdValueError> {
+        let declaration = self
+            .find_property_declaration(specification, host, &property.name)
+            .ok_or_else(|| FdValueError::UnknownProperty(property.name.clone()))?;
+        declaration.typed_value(&property.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FdValueError;
+    use crate::deploy::{FdPropertyHost, FdRootElement, FdValue};
+    use crate::parse_fdepl;
+
+    const DEPLOYMENT: &str = r#"package org.example
+
+specification someip {
+    for interfaces {
+        SomeIpServiceID : Integer;
+        SomeIpEventGroups : Integer[] (optional);
+        SomeIpName : String (optional);
+        SomeIpClients : Interface[] (default: {});
+        SomeIpStringEncoding : {utf8, utf16be, utf16le} (default: utf8);
+    }
+    for methods {
+        SomeIpReliable : Boolean;
+    }
+    for broadcasts {
+        SomeIpReliable : Integer;
+    }
+}
+
+define someip for interface org.example.HelloWorld {
+    SomeIpServiceID = 0x1234
+    SomeIpEventGroups = { 1, 0x10 }
+    SomeIpStringEncoding = utf16le
+    SomeIpClients = { org.example.Client }
+    SomeIpName = 42
+    SomeIpUnknown = true
+}
+
+define someip for interface org.example.Reliable {
+    method send {
+        SomeIpReliable = true
+    }
+    broadcast sent {
+        SomeIpReliable = 2
+    }
+}
+
+define someip for interface org.example.Broken {
+    SomeIpStringEncoding = utf32
+    SomeIpEventGroups = 1
+}
+"#;
+
+    #[test]
+    pub fn property_value_test() {
+        let model = parse_fdepl(DEPLOYMENT).unwrap();
+        let spec = model.find_specification("someip").unwrap();
+        let properties = match &model.deployments[0] {
+            FdRootElement::Interface(interface) => &interface.properties,
+            other => panic!("unexpected deployment {:?}", other),
+        };
+        let value = |name| {
+            model.property_value(
+                spec,
+                &FdPropertyHost::Interfaces,
+                properties.get(name).unwrap(),
+            )
+        };
+
+        assert_eq!(value("SomeIpServiceID"), Ok(FdValue::Integer(4660)));
+        assert_eq!(
+            value("SomeIpEventGroups"),
+            Ok(FdValue::Array(vec![
+                FdValue::Integer(1),
+                FdValue::Integer(16)
+            ]))
+        );
+        assert_eq!(
+            value("SomeIpStringEncoding"),
+            Ok(FdValue::Enumerator("utf16le".to_string()))
+        );
+        assert_eq!(
+            value("SomeIpClients"),
+            Ok(FdValue::Array(vec![FdValue::InterfaceRef(
+                "org.example.Client".to_string()
+            )]))
+        );
+        assert_eq!(
+            value("SomeIpName").unwrap_err().to_string(),
+            "value does not match type `String`"
+        );
+        assert_eq!(
+            value("SomeIpUnknown"),
+            Err(FdValueError::UnknownProperty("SomeIpUnknown".to_string()))
+        );
+
+        let properties = match &model.deployments[2] {
+            FdRootElement::Interface(interface) => &interface.properties,
+            other => panic!("unexpected deployment {:?}", other),
+        };
+        let value = |name| {
+            model.property_value(
+                spec,
+                &FdPropertyHost::Interfaces,
+                properties.get(name).unwrap(),
+            )
+        };
+        assert_eq!(
+            value("SomeIpStringEncoding"),
+            Err(FdValueError::UnknownEnumerator("utf32".to_string()))
+        );
+        assert_eq!(
+            value("SomeIpEventGroups").unwrap_err().to_string(),
+            "value does not match type `Integer[]`"
+        );
+    }
+
+    #[test]
+    pub fn property_value_host_test() {
+        let model = parse_fdepl(DEPLOYMENT).unwrap();
+        let spec = model.find_specification("someip").unwrap();
+        let interface = match &model.deployments[1] {
+            FdRootElement::Interface(interface) => interface,
+            other => panic!("unexpected deployment {:?}", other),
+        };
+        let method = interface.methods[0]
+            .properties
+            .get("SomeIpReliable")
+            .unwrap();
+        let broadcast = interface.broadcasts[0]
+            .properties
+            .get("SomeIpReliable")
+            .unwrap();
+
+        assert_eq!(
+            model.property_value(spec, &FdPropertyHost::Methods, method),
+            Ok(FdValue::Boolean(true))
+        );
+        assert_eq!(
+            model.property_value(spec, &FdPropertyHost::Broadcasts, broadcast),
+            Ok(FdValue::Integer(2))
+        );
+        assert_eq!(
+            model
+                .property_value(spec, &FdPropertyHost::Broadcasts, method)
+                .unwrap_err()
+                .to_string(),
+            "value does not match type `Integer`"
+        );
+        assert_eq!(
+            model.property_value(spec, &FdPropertyHost::Attributes, method),
+            Err(FdValueError::UnknownProperty("SomeIpReliable".to_string()))
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! Parser errors carrying the expected tokens and the enclosing model elements.
+
+use std::fmt;
+
+use nom::{
+    combinator::opt,
+    error::{ContextError, ErrorKind, FromExternalError},
+    sequence::tuple,
+    InputLength,
+};
+
+use crate::parser::{f_annotation_block, identifier, keyword, sp, Input};
+
+/// Token or construct the parser expected at the position of an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expected {
+    /// Keyword or punctuation, e.g. `method` or `=>`
+    Token(&'static str),
+    Char(char),
+    /// Named construct, e.g. `identifier`
+    Named(&'static str),
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expected::Token(token) => write!(f, "`{}`", token),
+            Expected::Char(c) => write!(f, "`{}`", c),
+            Expected::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Error type of the combinators in [`crate::parser`].
+///
+/// Alternatives failing at the same position merge their expected tokens,
+/// otherwise the error that got furthest into the input wins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error<I> {
+    /// Remaining input at the position of the error
+    pub input: I,
+    pub expected: Vec<Expected>,
+    /// Enclosing elements, innermost first, with the input at their start.
+    pub context: Vec<(I, &'static str)>,
+}
+
+impl<I> Error<I> {
+    pub fn expected(input: I, expected: Expected) -> Self {
+        Error {
+            input,
+            expected: vec![expected],
+            context: Vec::new(),
+        }
+    }
+}
+
+impl<I: InputLength> nom::error::ParseError<I> for Error<I> {
+    fn from_error_kind(input: I, _kind: ErrorKind) -> Self {
+        Error {
+            input,
+            expected: Vec::new(),
+            context: Vec::new(),
+        }
+    }
+
+    fn append(_input: I, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: I, c: char) -> Self {
+        Error::expected(input, Expected::Char(c))
+    }
+
+    fn or(mut self, other: Self) -> Self {
+        match self.input.input_len().cmp(&other.input.input_len()) {
+            std::cmp::Ordering::Less => self,
+            std::cmp::Ordering::Greater => other,
+            std::cmp::Ordering::Equal => {
+                for expected in other.expected {
+                    if !self.expected.contains(&expected) {
+                        self.expected.push(expected);
+                    }
+                }
+                if self.context.len() < other.context.len() {
+                    self.context = other.context;
+                }
+                self
+            }
+        }
+    }
+}
+
+impl<I> ContextError<I> for Error<I> {
+    fn add_context(input: I, context: &'static str, mut other: Self) -> Self {
+        other.context.push((input, context));
+        other
+    }
+}
+
+impl<I, E> FromExternalError<I, E> for Error<I> {
+    fn from_external_error(input: I, _kind: ErrorKind, _e: E) -> Self {
+        Error {
+            input,
+            expected: Vec::new(),
+            context: Vec::new(),
+        }
+    }
+}
+
+/// Failure of [`crate::parse_fidl`] or [`crate::parse_fdepl`], located in the parsed source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line of the error
+    pub line: usize,
+    /// 1-based column of the error, counted in characters
+    pub column: usize,
+    /// Byte offset of the error in the source
+    pub offset: usize,
+    /// Rendered expected tokens, e.g. `` `}` `` or `identifier`
+    pub expected: Vec<String>,
+    /// Word or character found at the error, `None` at the end of the input
+    pub found: Option<String>,
+    /// Enclosing elements, outermost first, e.g. `["interface HelloWorld", "method sayHello"]`
+    pub context: Vec<String>,
+    /// Source line containing the error
+    pub source_line: String,
+}
+
+impl ParseError {
+    /// Renders `error` for `source`, the input originally passed to the parser.
+    pub fn new(source: &str, error: Error<Input>) -> Self {
+        let offset = error.input.location_offset();
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[offset..]
+            .find('\n')
+            .map_or(source.len(), |i| offset + i);
+
+        let context = error
+            .context
+            .iter()
+            .rev()
+            .map(|(input, context)| match element_name(input, context) {
+                Some(name) => format!("{} {}", context, name),
+                None => context.to_string(),
+            })
+            .collect();
+
+        ParseError {
+            line: error.input.location_line() as usize,
+            column: error.input.get_utf8_column(),
+            offset,
+            expected: error.expected.iter().map(|e| e.to_string()).collect(),
+            found: found(error.input.fragment()),
+            context,
+            source_line: source[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.expected.as_slice() {
+            [] => write!(f, "error: unexpected input")?,
+            [expected] => write!(f, "error: expected {}", expected)?,
+            expected => write!(f, "error: expected one of {}", expected.join(", "))?,
+        }
+        match &self.found {
+            Some(found) => writeln!(f, ", found `{}`", found)?,
+            None => writeln!(f, ", found end of input")?,
+        }
+
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "{}--> {}:{}", gutter, self.line, self.column)?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(f, "{} | {}^", gutter, " ".repeat(self.column - 1))?;
+
+        if !self.context.is_empty() {
+            let context: Vec<_> = self.context.iter().map(|c| format!("in {}", c)).collect();
+            write!(f, "\n{} = {}", gutter, context.join(" > "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Word or single character at the start of `s`, e.g. `attribut` for `attribut UInt8 x`.
+fn found(s: &str) -> Option<String> {
+    let word_len = s
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(s.len());
+    match word_len {
+        0 => s.chars().next().map(String::from),
+        len => Some(s[..len].to_string()),
+    }
+}
+
+/// Name following the keyword of a context, e.g. `sayHello` for context `method`
+/// and input `method sayHello {`.
+fn element_name<'a>(input: &Input<'a>, context: &'static str) -> Option<&'a str> {
+    let context_keyword = context.split(' ').next()?;
+    let (s, _) = tuple((sp, opt(f_annotation_block), sp))(*input).ok()?;
+    let (s, _) = keyword(context_keyword)(s).ok()?;
+    let (s, _) = sp(s).ok()?;
+    let (_, name) = identifier(s).ok()?;
+    Some(name.fragment())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_fidl;
+
+    #[test]
+    pub fn parse_error_test() {
+        let def = "package test
+
+interface HelloWorld {
+    method sayHello {
+        in {
+            String name
+        }
+        out {
+            String 42
+        }
+    }
+}
+";
+        let error = parse_fidl(def).unwrap_err();
+        assert_eq!((error.line, error.column), (9, 20));
+        assert_eq!(error.expected, ["identifier"]);
+        assert_eq!(
+            error.context,
+            ["interface HelloWorld", "method sayHello", "out args"]
+        );
+        assert_eq!(
+            error.to_string(),
+            "error: expected identifier, found `42`
+ --> 9:20
+  |
+9 |             String 42
+  |                    ^
+  = in interface HelloWorld > in method sayHello > in out args"
+        );
+
+        let def = "package test
+
+typeCollection Types {
+    struct Point {
+        Int32 x
+    }
+    strukt Line {
+    }
+}
+";
+        let error = parse_fidl(def).unwrap_err();
+        assert_eq!((error.line, error.column), (7, 5));
+        assert_eq!(error.source_line, "    strukt Line {");
+        assert_eq!(error.context, ["typeCollection Types"]);
+        assert!(error.expected.contains(&"`}`".to_string()));
+        assert!(error.expected.contains(&"`struct`".to_string()));
+        assert_eq!(error.found.as_deref(), Some("strukt"));
+
+        let def = "package test
+
+interface HelloWorld {
+}
+attribut UInt8 x
+";
+        let error = parse_fidl(def).unwrap_err();
+        assert_eq!((error.line, error.column), (5, 1));
+        assert_eq!(error.found.as_deref(), Some("attribut"));
+        assert_eq!(
+            error.expected,
+            ["end of input", "`interface`", "`typeCollection`"]
+        );
+        assert!(error.context.is_empty());
+    }
+}
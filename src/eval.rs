@@ -0,0 +1,516 @@
+//! Folding of constant expressions to primitive values.
+
+use std::{cmp::Ordering, fmt};
+
+use crate::{
+    FBasicTypeId, FConstant, FConstantDef, FContainer, FEnumerationType, FEnumerator, FExpression,
+    FInitializerExpression, FIntegerInterval, FModel, FOperator, FPrimitiveType, FType, FTypeRef,
+    ResolveError,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    /// Referenced constant does not exist.
+    UnknownElement(String),
+    /// Referenced constant is defined by several equally near containers.
+    AmbiguousElement(String),
+    /// Field access on a constant, e.g. `ORIGIN->x`.
+    UnsupportedElementRef(String),
+    /// Operator is not applicable to the given operands.
+    InvalidOperands(FOperator),
+    DivisionByZero,
+    /// Integer arithmetic exceeded the 64 bit range.
+    Overflow,
+    /// Value can not be represented by the declared type.
+    TypeMismatch(FBasicTypeId),
+    /// Integer value lies outside of the declared interval.
+    OutOfInterval(FIntegerInterval),
+    /// Constant is not of a primitive type or has a compound or bracket initializer.
+    NotPrimitive(String),
+    /// Constant depends on its own value.
+    Cycle(String),
+    /// Floating point literal that can not be parsed.
+    InvalidLiteral(String),
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::UnknownElement(name) => write!(f, "unknown constant `{}`", name),
+            EvalError::AmbiguousElement(name) => write!(f, "ambiguous constant `{}`", name),
+            EvalError::UnsupportedElementRef(name) => {
+                write!(f, "field access on constant `{}` is not supported", name)
+            }
+            EvalError::InvalidOperands(op) => write!(f, "invalid operands for {:?}", op),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+            EvalError::Overflow => write!(f, "integer overflow"),
+            EvalError::TypeMismatch(type_id) => {
+                write!(f, "value can not be represented as {:?}", type_id)
+            }
+            EvalError::OutOfInterval(interval) => write!(
+                f,
+                "value is out of interval [{}, {}]",
+                interval
+                    .lower_bound
+                    .map_or("minInt".to_string(), |l| l.to_string()),
+                interval
+                    .upper_bound
+                    .map_or("maxInt".to_string(), |u| u.to_string()),
+            ),
+            EvalError::NotPrimitive(name) => write!(f, "constant `{}` is not primitive", name),
+            EvalError::Cycle(name) => write!(f, "constant `{}` depends on itself", name),
+            EvalError::InvalidLiteral(literal) => write!(f, "invalid literal `{}`", literal),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+impl From<ResolveError> for EvalError {
+    fn from(error: ResolveError) -> Self {
+        match error {
+            ResolveError::Unknown(name) => EvalError::UnknownElement(name),
+            ResolveError::Ambiguous(name) => EvalError::AmbiguousElement(name),
+        }
+    }
+}
+
+/// Intermediate value, integers are kept wide enough for `UInt64` and `Int64` operands.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Integer(i128),
+    Boolean(bool),
+    Float(f32),
+    Double(f64),
+    String(String),
+}
+
+impl FModel {
+    /// Evaluates an expression found within `container`, integers result in `Int64` values
+    /// or, beyond the `Int64` range, in `UInt64` values.
+    /// Referenced constants are resolved like types in [`FModel::find_type`].
+    pub fn evaluate<'a>(
+        &'a self,
+        expression: &FExpression,
+        container: Option<FContainer<'a>>,
+    ) -> Result<FPrimitiveType, EvalError> {
+        let value = self.eval(expression, container, &mut Vec::new())?;
+        match value {
+            Value::Integer(i) => wide_integer(i).ok_or(EvalError::Overflow),
+            value => Ok(into_primitive(value)),
+        }
+    }
+
+    /// Evaluates the value of a constant and converts it to the declared type.
+    pub fn evaluate_constant(&self, constant: &FConstantDef) -> Result<FPrimitiveType, EvalError> {
+        self.eval_constant(constant, &mut Vec::new())
+    }
+
+    /// Values of all enumerators of an enumeration including inherited ones, base enumerators first.
+    ///
+    /// Like the CommonAPI generator, enumerators without an explicit value continue counting
+    /// from the preceding enumerator, starting at `0` and skipping values assigned explicitly.
+    pub fn enumerator_values<'a>(
+        &'a self,
+        enumeration: &'a FEnumerationType,
+    ) -> Result<Vec<(&'a FEnumerator, i64)>, EvalError> {
+        let enumerators = self.all_enumerators(enumeration);
+
+        let mut explicit = Vec::new();
+        for enumeration in self.enumeration_hierarchy(enumeration) {
+            let container = self.enumeration_container(enumeration);
+            for value in enumeration
+                .enumerators
+                .iter()
+                .filter_map(|e| e.value.as_ref())
+            {
+                explicit.push(match self.evaluate(value, container)? {
+                    FPrimitiveType::Int64(value) => value,
+                    _ => return Err(EvalError::TypeMismatch(FBasicTypeId::Int64)),
+                });
+            }
+        }
+
+        let mut explicit_values = explicit.iter();
+        let mut next = Some(0);
+        let mut values = Vec::with_capacity(enumerators.len());
+        for enumerator in enumerators {
+            let value = match enumerator.value {
+                Some(_) => *explicit_values.next().expect("evaluated above"),
+                None => {
+                    let mut value = next.ok_or(EvalError::Overflow)?;
+                    while explicit.contains(&value) {
+                        value = value.checked_add(1).ok_or(EvalError::Overflow)?;
+                    }
+                    value
+                }
+            };
+            next = value.checked_add(1);
+            values.push((enumerator, value));
+        }
+        Ok(values)
+    }
+
+    fn eval_constant<'a>(
+        &'a self,
+        constant: &'a FConstantDef,
+        stack: &mut Vec<&'a FConstantDef>,
+    ) -> Result<FPrimitiveType, EvalError> {
+        if stack.iter().any(|c| std::ptr::eq(*c, constant)) {
+            return Err(EvalError::Cycle(constant.name.clone()));
+        }
+
+        let expression = match (&constant.rhs, constant.array) {
+            (FInitializerExpression::Expression(expression), false) => expression,
+            _ => return Err(EvalError::NotPrimitive(constant.name.clone())),
+        };
+        let container = self.constant_container(constant);
+        let (type_id, interval) = self
+            .primitive_type(&constant.r#type, container)
+            .ok_or_else(|| EvalError::NotPrimitive(constant.name.clone()))?;
+
+        stack.push(constant);
+        let value = self.eval(expression, container, stack);
+        stack.pop();
+
+        convert(value?, type_id, interval)
+    }
+
+    /// Follows typedefs down to a predefined type and the interval it is restricted to.
+    fn primitive_type<'a>(
+        &'a self,
+        type_ref: &'a FTypeRef,
+        container: Option<FContainer<'a>>,
+    ) -> Option<(FBasicTypeId, Option<FIntegerInterval>)> {
+        let mut type_ref = type_ref;
+        let mut container = container;
+        let mut visited = Vec::new();
+        loop {
+            if let Some(interval) = type_ref.interval {
+                return Some((FBasicTypeId::Integer, Some(interval)));
+            }
+            if let Some(type_id) = type_ref.predefined {
+                return Some((type_id, None));
+            }
+            let name = type_ref.derived.as_deref()?;
+            let (type_container, r#type) = self.lookup_type(name, container).ok()?;
+            container = Some(type_container);
+            match r#type {
+                FType::TypeDef(type_def) => {
+                    if type_def.array || visited.iter().any(|t| std::ptr::eq(*t, type_def)) {
+                        return None;
+                    }
+                    visited.push(type_def);
+                    type_ref = &type_def.actual_type;
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn eval<'a>(
+        &'a self,
+        expression: &FExpression,
+        container: Option<FContainer<'a>>,
+        stack: &mut Vec<&'a FConstantDef>,
+    ) -> Result<Value, EvalError> {
+        match expression {
+            FExpression::Constant(constant) => literal(constant),
+            FExpression::ElementRef(element_ref) => {
+                if !element_ref.fields.is_empty() {
+                    return Err(EvalError::UnsupportedElementRef(
+                        element_ref.element.clone(),
+                    ));
+                }
+                let constant = self.find_constant(&element_ref.element, container)?;
+                let value = self.eval_constant(constant, stack)?;
+                from_primitive(value).ok_or_else(|| EvalError::NotPrimitive(constant.name.clone()))
+            }
+            FExpression::Unary { op, operand } => unary(*op, self.eval(operand, container, stack)?),
+            FExpression::Binary { left, op, right } => {
+                let left = self.eval(left, container, stack)?;
+                let right = self.eval(right, container, stack)?;
+                binary(left, *op, right)
+            }
+        }
+    }
+}
+
+fn literal(constant: &FConstant) -> Result<Value, EvalError> {
+    let invalid = |literal: &String| EvalError::InvalidLiteral(literal.clone());
+    Ok(match constant {
+        FConstant::Boolean(b) => Value::Boolean(*b),
+        FConstant::Integer(i) => Value::Integer(*i),
+        FConstant::Float(f) => Value::Float(f.parse().map_err(|_| invalid(f))?),
+        FConstant::Double(d) => Value::Double(d.parse().map_err(|_| invalid(d))?),
+        FConstant::String(s) => Value::String(s.clone()),
+    })
+}
+
+fn unary(op: FOperator, operand: Value) -> Result<Value, EvalError> {
+    match (op, operand) {
+        (FOperator::Not, Value::Boolean(b)) => Ok(Value::Boolean(!b)),
+        (FOperator::Subtraction, Value::Integer(i)) => Ok(Value::Integer(-i)),
+        (FOperator::Subtraction, Value::Float(f)) => Ok(Value::Float(-f)),
+        (FOperator::Subtraction, Value::Double(d)) => Ok(Value::Double(-d)),
+        (op, _) => Err(EvalError::InvalidOperands(op)),
+    }
+}
+
+fn binary(left: Value, op: FOperator, right: Value) -> Result<Value, EvalError> {
+    use FOperator::*;
+
+    let invalid = || EvalError::InvalidOperands(op);
+    match (op, left, right) {
+        (And, Value::Boolean(l), Value::Boolean(r)) => Ok(Value::Boolean(l && r)),
+        (Or, Value::Boolean(l), Value::Boolean(r)) => Ok(Value::Boolean(l || r)),
+        (Equal | Unequal, Value::Boolean(l), Value::Boolean(r)) => {
+            Ok(Value::Boolean((l == r) == (op == Equal)))
+        }
+        (Equal | Unequal | Smaller | SmallerOrEqual | GreaterOrEqual | Greater, l, r) => {
+            let ordering = match (l, r) {
+                (Value::String(l), Value::String(r)) => l.partial_cmp(&r),
+                (Value::Integer(l), Value::Integer(r)) => l.partial_cmp(&r),
+                (l, r) => as_double(&l)
+                    .zip(as_double(&r))
+                    .ok_or_else(invalid)
+                    .map(|(l, r)| l.partial_cmp(&r))?,
+            };
+            let result = match op {
+                Equal => ordering.is_some_and(Ordering::is_eq),
+                Unequal => !ordering.is_some_and(Ordering::is_eq),
+                Smaller => ordering.is_some_and(Ordering::is_lt),
+                SmallerOrEqual => ordering.is_some_and(Ordering::is_le),
+                GreaterOrEqual => ordering.is_some_and(Ordering::is_ge),
+                _ => ordering.is_some_and(Ordering::is_gt),
+            };
+            Ok(Value::Boolean(result))
+        }
+        (
+            Addition | Subtraction | Multiplication | Division,
+            Value::Integer(l),
+            Value::Integer(r),
+        ) => {
+            let result = match op {
+                Addition => l.checked_add(r),
+                Subtraction => l.checked_sub(r),
+                Multiplication => l.checked_mul(r),
+                _ if r == 0 => return Err(EvalError::DivisionByZero),
+                _ => l.checked_div(r),
+            };
+            result
+                .filter(|i| i64::try_from(*i).is_ok() || u64::try_from(*i).is_ok())
+                .map(Value::Integer)
+                .ok_or(EvalError::Overflow)
+        }
+        (Addition | Subtraction | Multiplication | Division, l, r) => {
+            let single = !matches!(l, Value::Double(_)) && !matches!(r, Value::Double(_));
+            let (l, r) = as_double(&l).zip(as_double(&r)).ok_or_else(invalid)?;
+            let result = match op {
+                Addition => l + r,
+                Subtraction => l - r,
+                Multiplication => l * r,
+                _ => l / r,
+            };
+            Ok(if single {
+                Value::Float(result as f32)
+            } else {
+                Value::Double(result)
+            })
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn as_double(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(i) => Some(*i as f64),
+        Value::Float(f) => Some((*f).into()),
+        Value::Double(d) => Some(*d),
+        _ => None,
+    }
+}
+
+fn from_primitive(value: FPrimitiveType) -> Option<Value> {
+    Some(match value {
+        FPrimitiveType::UInt8(i) => Value::Integer(i.into()),
+        FPrimitiveType::Int8(i) => Value::Integer(i.into()),
+        FPrimitiveType::UInt16(i) => Value::Integer(i.into()),
+        FPrimitiveType::Int16(i) => Value::Integer(i.into()),
+        FPrimitiveType::UInt32(i) => Value::Integer(i.into()),
+        FPrimitiveType::Int32(i) => Value::Integer(i.into()),
+        FPrimitiveType::UInt64(i) => Value::Integer(i.into()),
+        FPrimitiveType::Int64(i) => Value::Integer(i.into()),
+        FPrimitiveType::Boolean(b) => Value::Boolean(b),
+        FPrimitiveType::Float(f) => Value::Float(f),
+        FPrimitiveType::Double(d) => Value::Double(d),
+        FPrimitiveType::String(s) => Value::String(s),
+        FPrimitiveType::ByteBuffer(_) => return None,
+    })
+}
+
+/// Only used for non integer values, which need no range check.
+fn into_primitive(value: Value) -> FPrimitiveType {
+    match value {
+        Value::Integer(i) => FPrimitiveType::Int64(i as i64),
+        Value::Boolean(b) => FPrimitiveType::Boolean(b),
+        Value::Float(f) => FPrimitiveType::Float(f),
+        Value::Double(d) => FPrimitiveType::Double(d),
+        Value::String(s) => FPrimitiveType::String(s),
+    }
+}
+
+/// Converts `value` to the declared type, integers are range checked.
+fn convert(
+    value: Value,
+    type_id: FBasicTypeId,
+    interval: Option<FIntegerInterval>,
+) -> Result<FPrimitiveType, EvalError> {
+    let mismatch = || EvalError::TypeMismatch(type_id);
+
+    Ok(match (type_id, value) {
+        (FBasicTypeId::UInt8, Value::Integer(i)) => FPrimitiveType::UInt8(narrow(i, type_id)?),
+        (FBasicTypeId::Int8, Value::Integer(i)) => FPrimitiveType::Int8(narrow(i, type_id)?),
+        (FBasicTypeId::UInt16, Value::Integer(i)) => FPrimitiveType::UInt16(narrow(i, type_id)?),
+        (FBasicTypeId::Int16, Value::Integer(i)) => FPrimitiveType::Int16(narrow(i, type_id)?),
+        (FBasicTypeId::UInt32, Value::Integer(i)) => FPrimitiveType::UInt32(narrow(i, type_id)?),
+        (FBasicTypeId::Int32, Value::Integer(i)) => FPrimitiveType::Int32(narrow(i, type_id)?),
+        (FBasicTypeId::UInt64, Value::Integer(i)) => FPrimitiveType::UInt64(narrow(i, type_id)?),
+        (FBasicTypeId::Int64, Value::Integer(i)) => FPrimitiveType::Int64(narrow(i, type_id)?),
+        (FBasicTypeId::Integer | FBasicTypeId::Undefined, Value::Integer(i)) => {
+            if let Some(interval) = interval.filter(|interval| !interval.contains(i)) {
+                return Err(EvalError::OutOfInterval(interval));
+            }
+            wide_integer(i).ok_or(EvalError::TypeMismatch(type_id))?
+        }
+        (FBasicTypeId::Boolean, Value::Boolean(b)) => FPrimitiveType::Boolean(b),
+        (FBasicTypeId::Float, value) => {
+            FPrimitiveType::Float(as_double(&value).ok_or_else(mismatch)? as f32)
+        }
+        (FBasicTypeId::Double, value) => {
+            FPrimitiveType::Double(as_double(&value).ok_or_else(mismatch)?)
+        }
+        (FBasicTypeId::String, Value::String(s)) => FPrimitiveType::String(s),
+        _ => return Err(mismatch()),
+    })
+}
+
+/// `Int64` if possible, `UInt64` for larger values.
+fn wide_integer(value: i128) -> Option<FPrimitiveType> {
+    i64::try_from(value)
+        .map(FPrimitiveType::Int64)
+        .or_else(|_| u64::try_from(value).map(FPrimitiveType::UInt64))
+        .ok()
+}
+
+fn narrow<T: TryFrom<i128>>(value: i128, type_id: FBasicTypeId) -> Result<T, EvalError> {
+    T::try_from(value).map_err(|_| EvalError::TypeMismatch(type_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EvalError;
+    use crate::{parse_fidl, FPrimitiveType, FType};
+
+    const MODEL: &str = "package org.example
+
+typeCollection Limits {
+    typedef Percent is Integer(0, 100)
+
+    const UInt8 MAX = 200
+    const UInt16 DOUBLE_MAX = MAX * 2
+    const UInt8 TOO_BIG = MAX + 100
+    const Percent HALF = 100 / 2
+    const Percent OVER = 101
+    const Boolean ENABLED = !false && DOUBLE_MAX > 300
+    const Double RATIO = 1.5d * 2
+    const String NAME = \"limits\"
+    const Int32 CYCLE = OTHER + 1
+    const Int32 OTHER = Limits.CYCLE
+    const UInt64 U64_MAX = 0xFFFFFFFFFFFFFFFF
+    const Int64 I64_MAX = U64_MAX / 2
+    const Int64 TOO_BIG_I64 = U64_MAX
+    const Integer(0, maxInt) WIDE = U64_MAX - 1
+}
+
+interface Sensor {
+    enumeration Base {
+        A = 0x10
+        B
+    }
+
+    enumeration Mode extends Base {
+        C = 0b0101
+        D
+        E = Limits.MAX + 4
+        F = 3 + 4
+        G
+    }
+
+    const Int8 OFFSET = -(Limits.MAX / 20)
+    const UInt32[] VALUES = [1, 2, 3]
+}
+";
+
+    #[test]
+    pub fn evaluate_constant_test() {
+        let model = parse_fidl(MODEL).unwrap();
+        let eval = |name: &str| model.evaluate_constant(model.find_constant(name, None).unwrap());
+
+        assert_eq!(eval("MAX"), Ok(FPrimitiveType::UInt8(200)));
+        assert_eq!(eval("DOUBLE_MAX"), Ok(FPrimitiveType::UInt16(400)));
+        assert_eq!(
+            eval("TOO_BIG"),
+            Err(EvalError::TypeMismatch(crate::FBasicTypeId::UInt8))
+        );
+        assert_eq!(eval("HALF"), Ok(FPrimitiveType::Int64(50)));
+        assert!(matches!(eval("OVER"), Err(EvalError::OutOfInterval(_))));
+        assert_eq!(eval("ENABLED"), Ok(FPrimitiveType::Boolean(true)));
+        assert_eq!(eval("RATIO"), Ok(FPrimitiveType::Double(3.0)));
+        assert_eq!(
+            eval("NAME"),
+            Ok(FPrimitiveType::String("limits".to_string()))
+        );
+        assert_eq!(eval("CYCLE"), Err(EvalError::Cycle("CYCLE".to_string())));
+        assert_eq!(eval("U64_MAX"), Ok(FPrimitiveType::UInt64(u64::MAX)));
+        assert_eq!(eval("I64_MAX"), Ok(FPrimitiveType::Int64(i64::MAX)));
+        assert_eq!(
+            eval("TOO_BIG_I64"),
+            Err(EvalError::TypeMismatch(crate::FBasicTypeId::Int64))
+        );
+        assert_eq!(eval("WIDE"), Ok(FPrimitiveType::UInt64(u64::MAX - 1)));
+        assert_eq!(eval("Sensor.OFFSET"), Ok(FPrimitiveType::Int8(-10)));
+        assert_eq!(
+            eval("org.example.Sensor.VALUES"),
+            Err(EvalError::NotPrimitive("VALUES".to_string()))
+        );
+    }
+
+    #[test]
+    pub fn enumerator_values_test() {
+        let model = parse_fidl(MODEL).unwrap();
+        let mode = match model.find_type("Sensor.Mode", None) {
+            Ok(FType::Enumeration(mode)) => mode,
+            _ => panic!("enumeration Mode not found"),
+        };
+
+        let values: Vec<_> = model
+            .enumerator_values(mode)
+            .unwrap()
+            .into_iter()
+            .map(|(enumerator, value)| (enumerator.name.as_str(), value))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                ("A", 16),
+                ("B", 17),
+                ("C", 5),
+                ("D", 6),
+                ("E", 204),
+                ("F", 7),
+                ("G", 8)
+            ]
+        );
+    }
+}
@@ -1,10 +1,21 @@
 #![doc = include_str!("../README.md")]
 
 pub mod deploy;
+pub mod error;
+pub mod eval;
 pub mod parser;
+mod resolve;
+
+pub use deploy::parse_fdepl;
+pub use error::ParseError;
+pub use parser::parse_fidl;
+pub use resolve::ResolveError;
+
+use std::fmt;
 
 /// Primitive types
 /// Franca IDL Ref: 5.1.1
+#[derive(Debug, Clone, PartialEq)]
 pub enum FPrimitiveType {
     UInt8(u8),
     Int8(i8),
@@ -21,6 +32,31 @@ pub enum FPrimitiveType {
     ByteBuffer(Vec<u8>),
 }
 
+/// Location of a model element within the parsed source.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    /// Byte offset of the first character
+    pub start: usize,
+    /// Byte offset behind the last character
+    pub end: usize,
+    /// 1-based line of the first character
+    pub line: u32,
+    /// 1-based column of the first character, counted in characters
+    pub column: usize,
+    pub end_line: u32,
+    pub end_column: usize,
+}
+
+impl fmt::Debug for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}..{}:{}",
+            self.line, self.column, self.end_line, self.end_column
+        )
+    }
+}
+
 /// Root of franca model.
 /// Franca IDL Ref: 8.4.1
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -30,6 +66,7 @@ pub struct FModel {
     pub imports: Vec<Import>,
     pub interfaces: Vec<FInterface>,
     pub type_collections: Vec<FTypeCollection>,
+    pub span: Span,
 }
 
 /// Collection of Franca type definitions.
@@ -39,8 +76,42 @@ pub struct FTypeCollection {
     pub name: Option<String>,
     pub comment: Option<FAnnotationBlock>,
     pub version: Option<FVersion>,
-    // TODO
-    // types: Vec<FType>,
+    pub types: Vec<FType>,
+    pub constants: Vec<FConstantDef>,
+    pub span: Span,
+}
+
+/// Interface or type collection defining types and constants.
+/// Names referenced from within a container are resolved relative to it,
+/// see [`FModel::find_type`].
+#[derive(Debug, Clone, Copy)]
+pub enum FContainer<'a> {
+    Interface(&'a FInterface),
+    TypeCollection(&'a FTypeCollection),
+}
+
+impl<'a> FContainer<'a> {
+    /// `None` for anonymous type collections.
+    pub fn name(&self) -> Option<&'a str> {
+        match self {
+            FContainer::Interface(interface) => Some(&interface.name),
+            FContainer::TypeCollection(type_collection) => type_collection.name.as_deref(),
+        }
+    }
+
+    pub fn types(&self) -> &'a [FType] {
+        match self {
+            FContainer::Interface(interface) => &interface.types,
+            FContainer::TypeCollection(type_collection) => &type_collection.types,
+        }
+    }
+
+    pub fn constants(&self) -> &'a [FConstantDef] {
+        match self {
+            FContainer::Interface(interface) => &interface.constants,
+            FContainer::TypeCollection(type_collection) => &type_collection.constants,
+        }
+    }
 }
 
 /// Franca IDL Ref: 8.4.3
@@ -49,24 +120,36 @@ pub struct FInterface {
     pub name: String,
     pub comment: Option<FAnnotationBlock>,
     pub version: Option<FVersion>,
-    // types: Vec<FType>,
+    pub types: Vec<FType>,
+    pub constants: Vec<FConstantDef>,
     pub attributes: Vec<FAttribute>,
     pub methods: Vec<FMethod>,
     pub broadcasts: Vec<FBroadcast>,
-    // contract: Option<FContract>,
-    // TODO recursive type
-    //base: Option<FInterface>,
-    // TODO recursive type
-    // managed_interfaces: Vec<FInterface>,
+    pub contract: Option<FContract>,
+    /// Possibly qualified name of the extended interface.
+    pub base: Option<String>,
+    /// Possibly qualified names of the interfaces listed after `manages`.
+    pub managed_interfaces: Vec<String>,
+    pub span: Span,
 }
 
 /// Franca IDL Ref: 8.4.4
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FBroadcast {
     pub name: String,
+    /// Distinguishes overloaded broadcasts, e.g. `v2` in `broadcast changed:v2`.
+    pub selector: Option<String>,
     pub comment: Option<FAnnotationBlock>,
     pub out_args: Vec<FArgument>,
-    pub selective: Option<String>,
+    pub selective: Option<bool>,
+    pub span: Span,
+}
+
+impl FBroadcast {
+    /// Name including the selector, e.g. `changed:v2`.
+    pub fn name_with_selector(&self) -> String {
+        name_with_selector(&self.name, self.selector.as_deref())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -74,18 +157,38 @@ pub struct FArgument {
     pub name: String,
     pub comment: Option<FAnnotationBlock>,
     pub r#type: FTypeRef,
-    pub array: Option<String>,
+    pub array: bool,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FMethod {
     pub name: String,
+    /// Distinguishes overloaded methods, e.g. `v2` in `method foo:v2`.
+    pub selector: Option<String>,
     pub comment: Option<FAnnotationBlock>,
-    pub fire_and_forget: Option<String>,
+    pub fire_and_forget: Option<bool>,
     pub in_args: Vec<FArgument>,
     pub out_args: Vec<FArgument>,
-    pub error_enum: Option<FEnumerationType>,
+    /// Possibly qualified name of the enumeration referenced by `error SomeEnum`.
+    pub error_enum: Option<String>,
+    /// Inline error enumeration, e.g. `error { A B C }`, its name is empty.
     pub errors: Option<FEnumerationType>,
+    pub span: Span,
+}
+
+impl FMethod {
+    /// Name including the selector, e.g. `foo:v2`.
+    pub fn name_with_selector(&self) -> String {
+        name_with_selector(&self.name, self.selector.as_deref())
+    }
+}
+
+fn name_with_selector(name: &str, selector: Option<&str>) -> String {
+    match selector {
+        Some(selector) => format!("{}:{}", name, selector),
+        None => name.to_string(),
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -93,15 +196,257 @@ pub struct FAttribute {
     pub name: String,
     pub comment: Option<FAnnotationBlock>,
     pub r#type: FTypeRef,
-    pub array: Option<String>,
+    pub array: bool,
     pub readonly: Option<bool>,
     pub no_subscriptions: Option<bool>,
+    pub span: Span,
+}
+
+/// Interface contract, a protocol state machine with optional state variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FContract {
+    pub variables: Vec<FDeclaration>,
+    pub state_graph: FStateGraph,
+    pub span: Span,
+}
+
+/// State variable declared in the `vars` block of a contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FDeclaration {
+    pub name: String,
+    pub r#type: FTypeRef,
+    pub array: bool,
+    pub rhs: Option<FInitializerExpression>,
+    pub span: Span,
+}
+
+/// e.g. `const UInt32 MAX = 100` or `const Point ORIGIN = { x: 0, y: 0 }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FConstantDef {
+    pub name: String,
+    pub comment: Option<FAnnotationBlock>,
+    pub r#type: FTypeRef,
+    pub array: bool,
+    pub rhs: FInitializerExpression,
+    pub span: Span,
+}
+
+/// Right hand side of constant definitions and state variable declarations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FInitializerExpression {
+    Expression(FExpression),
+    /// Struct or union initializer, e.g. `{ x: 0, y: 0 }`
+    Compound(Vec<FFieldInitializer>),
+    /// Array or map initializer, e.g. `[1, 2, 3]` or `["a" => 1]`
+    Bracket(Vec<FElementInitializer>),
+}
+
+/// e.g. `x: 0`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FFieldInitializer {
+    /// Name of the initialized field
+    pub element: String,
+    pub value: FInitializerExpression,
+}
+
+/// Array element or, if `second` is present, map entry `first => second`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FElementInitializer {
+    pub first: FInitializerExpression,
+    pub second: Option<FInitializerExpression>,
+}
+
+/// Protocol state machine, `PSM { initial s1 state s1 { ... } }`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FStateGraph {
+    /// Name of the initial state
+    pub initial: String,
+    pub states: Vec<FState>,
+    pub span: Span,
+}
+
+impl FStateGraph {
+    pub fn state(&self, name: &str) -> Option<&FState> {
+        self.states.iter().find(|s| s.name == name)
+    }
+
+    /// First transition leaving `state` that is triggered by `event`.
+    /// Guards are not evaluated, so conformance checks of traces have to do so themselves
+    /// if a state has several transitions for the same event.
+    pub fn step(&self, state: &str, event: &FEventOnIf) -> Option<&FTransition> {
+        self.state(state)?
+            .transitions
+            .iter()
+            .find(|t| &t.trigger.event == event)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FState {
+    pub name: String,
+    pub transitions: Vec<FTransition>,
+    pub span: Span,
+}
+
+/// e.g. `on call foo [count < 3] -> s2 { count += 1 }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FTransition {
+    pub trigger: FTrigger,
+    pub guard: Option<FGuard>,
+    /// Name of the target state
+    pub to: String,
+    pub action: Option<FBlockExpression>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FTrigger {
+    pub event: FEventOnIf,
+}
+
+/// Interface event triggering a transition,
+/// each referencing a method, broadcast or attribute by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FEventOnIf {
+    /// Client calls a method, the name may carry a selector, e.g. `foo:v2`.
+    Call(String),
+    /// Server responds to a method call.
+    Respond(String),
+    /// Server fires a broadcast.
+    Signal(String),
+    /// Server responds to a method call with an error.
+    Error(String),
+    /// Client sets an attribute.
+    Set(String),
+    /// Server updates an attribute.
+    Update(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FGuard {
+    pub condition: FExpression,
+}
+
+/// Braced sequence of statements.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FBlockExpression {
+    pub statements: Vec<FStatement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FStatement {
+    If {
+        condition: FExpression,
+        then: Box<FStatement>,
+        r#else: Option<Box<FStatement>>,
+    },
+    Assignment {
+        lhs: FQualifiedElementRef,
+        op: FAssignmentOp,
+        rhs: FExpression,
+    },
+    Block(FBlockExpression),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FAssignmentOp {
+    /// `=`
+    Assign,
+    /// `*=`
+    MulAssign,
+    /// `/=`
+    DivAssign,
+    /// `%=`
+    ModAssign,
+    /// `+=`
+    AddAssign,
+    /// `-=`
+    SubAssign,
+    /// `<<=`
+    ShiftLeftAssign,
+    /// `>>=`
+    ShiftRightAssign,
+    /// `&=`
+    AndAssign,
+    /// `^=`
+    XorAssign,
+    /// `|=`
+    OrAssign,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FExpression {
+    Constant(FConstant),
+    ElementRef(FQualifiedElementRef),
+    Unary {
+        op: FOperator,
+        operand: Box<FExpression>,
+    },
+    Binary {
+        left: Box<FExpression>,
+        op: FOperator,
+        right: Box<FExpression>,
+    },
+}
+
+/// Literal value of an expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FConstant {
+    Boolean(bool),
+    /// Franca integers are of arbitrary size, `i128` covers the `UInt64` and `Int64` ranges.
+    Integer(i128),
+    /// Float literal as written without the `f` suffix, kept as text so that the model stays `Eq`.
+    Float(String),
+    /// Double literal as written without the `d` suffix, kept as text so that the model stays `Eq`.
+    Double(String),
+    String(String),
+}
+
+/// Reference to a model element, e.g. a state variable or constant,
+/// optionally followed by field accesses like `position->latitude`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FQualifiedElementRef {
+    /// Possibly qualified name of the referenced element
+    pub element: String,
+    /// Names of the accessed fields in order
+    pub fields: Vec<String>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FOperator {
+    /// `&&`
+    And,
+    /// `||`
+    Or,
+    /// `==`
+    Equal,
+    /// `!=`
+    Unequal,
+    /// `<`
+    Smaller,
+    /// `<=`
+    SmallerOrEqual,
+    /// `>=`
+    GreaterOrEqual,
+    /// `>`
+    Greater,
+    /// `+`
+    Addition,
+    /// `-`, also used for negation
+    Subtraction,
+    /// `*`
+    Multiplication,
+    /// `/`
+    Division,
+    /// `!`
+    Not,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct FVersion {
     pub major: u16,
     pub minor: u16,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -110,31 +455,115 @@ pub struct Import {
     pub imported_namespace: Option<String>,
     /// Uri of the imported resource
     pub import_uri: Option<String>,
+    pub span: Span,
 }
 
-// TODO
-// Abstract
-// pub struct FType {
-//     name: String,
-//     comment: Option<FAnnotationBlock>,
-// }
+/// User defined type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FType {
+    Array(FArrayType),
+    Enumeration(FEnumerationType),
+    Struct(FStructType),
+    Union(FUnionType),
+    Map(FMapType),
+    TypeDef(FTypeDef),
+}
 
-// pub fn f_type(s: &str) -> IResult<&str, FBasicTypeId> {
+impl FType {
+    pub fn name(&self) -> &str {
+        match self {
+            FType::Array(t) => &t.name,
+            FType::Enumeration(t) => &t.name,
+            FType::Struct(t) => &t.name,
+            FType::Union(t) => &t.name,
+            FType::Map(t) => &t.name,
+            FType::TypeDef(t) => &t.name,
+        }
+    }
 
-// }
+    pub fn comment(&self) -> Option<&FAnnotationBlock> {
+        match self {
+            FType::Array(t) => t.comment.as_ref(),
+            FType::Enumeration(t) => t.comment.as_ref(),
+            FType::Struct(t) => t.comment.as_ref(),
+            FType::Union(t) => t.comment.as_ref(),
+            FType::Map(t) => t.comment.as_ref(),
+            FType::TypeDef(t) => t.comment.as_ref(),
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            FType::Array(t) => t.span,
+            FType::Enumeration(t) => t.span,
+            FType::Struct(t) => t.span,
+            FType::Union(t) => t.span,
+            FType::Map(t) => t.span,
+            FType::TypeDef(t) => t.span,
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FMapType {
     pub name: String,
     pub comment: Option<FAnnotationBlock>,
     pub key_type: FTypeRef,
+    pub key_array: bool,
     pub value_type: FTypeRef,
+    pub value_array: bool,
+    pub span: Span,
 }
 
+/// Reference to either a predefined basic type or a user defined type.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FTypeRef {
     pub predefined: Option<FBasicTypeId>,
-    // derived: Option<FType>,
+    /// Possibly qualified name of the referenced user defined type,
+    /// see [`FModel::resolve_type_ref`].
+    pub derived: Option<String>,
+    /// Value range of an `Integer(min, max)` type.
+    pub interval: Option<FIntegerInterval>,
+    pub span: Span,
+}
+
+/// Range of an integer type, `None` bounds are open (`minInt`, `maxInt`).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FIntegerInterval {
+    pub lower_bound: Option<i128>,
+    pub upper_bound: Option<i128>,
+}
+
+impl FIntegerInterval {
+    /// Whether `value` lies within the interval, bounds are inclusive.
+    pub fn contains(&self, value: i128) -> bool {
+        self.lower_bound.is_none_or(|lower| lower <= value)
+            && self.upper_bound.is_none_or(|upper| value <= upper)
+    }
+
+    /// Smallest fixed size integer type able to represent every value of the interval.
+    /// Unsigned types are preferred for intervals without negative values,
+    /// open bounds are taken as the smallest `Int64` and the largest `UInt64` value.
+    pub fn narrowest_type(&self) -> FBasicTypeId {
+        let lower = self.lower_bound.unwrap_or(i64::MIN.into());
+        let upper = self.upper_bound.unwrap_or(u64::MAX.into());
+
+        let candidates: [(FBasicTypeId, i128, i128); 8] = [
+            (FBasicTypeId::UInt8, u8::MIN.into(), u8::MAX.into()),
+            (FBasicTypeId::Int8, i8::MIN.into(), i8::MAX.into()),
+            (FBasicTypeId::UInt16, u16::MIN.into(), u16::MAX.into()),
+            (FBasicTypeId::Int16, i16::MIN.into(), i16::MAX.into()),
+            (FBasicTypeId::UInt32, u32::MIN.into(), u32::MAX.into()),
+            (FBasicTypeId::Int32, i32::MIN.into(), i32::MAX.into()),
+            (FBasicTypeId::UInt64, u64::MIN.into(), u64::MAX.into()),
+            (FBasicTypeId::Int64, i64::MIN.into(), i64::MAX.into()),
+        ];
+
+        candidates
+            .iter()
+            .find(|(_, min, max)| *min <= lower && upper <= *max)
+            .map_or(FBasicTypeId::Int64, |(type_id, _, _)| *type_id)
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -161,21 +590,19 @@ pub struct FEnumerationType {
     pub name: String,
     pub comment: Option<FAnnotationBlock>,
     pub enumerators: Vec<FEnumerator>,
-    // TODO recursive type
-    // base: Option<FEnumerationType>,
+    /// Possibly qualified name of the extended enumeration.
+    pub base: Option<String>,
+    pub span: Span,
 }
 
-// TODO
-// pub fn f_enumeration_type(s: &str) -> IResult<&str, FEnumerationType> {
-//     let (s, _) = tag("enumeration ");
-//     let (s; name) =
-// }
-
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FEnumerator {
     pub name: String,
     pub comment: Option<FAnnotationBlock>,
-    pub value: Option<String>,
+    /// Explicit value, e.g. `0x10` or `BASE + 1`.
+    /// See [`FModel::enumerator_values`] for the values of implicitly numbered enumerators.
+    pub value: Option<FExpression>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -183,6 +610,9 @@ pub struct FTypeDef {
     pub name: String,
     pub comment: Option<FAnnotationBlock>,
     pub actual_type: FTypeRef,
+    /// `typedef Bytes is UInt8[]`
+    pub array: bool,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -198,8 +628,9 @@ pub struct FUnionType {
     pub name: String,
     pub comment: Option<FAnnotationBlock>,
     pub elements: Vec<FField>,
-    // TODO recursive type
-    // base: Option<FUnionType>,
+    /// Possibly qualified name of the extended union.
+    pub base: Option<String>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -207,9 +638,10 @@ pub struct FStructType {
     pub name: String,
     pub comment: Option<FAnnotationBlock>,
     pub elements: Vec<FField>,
-    // TODO recursive type
-    // base: Option<Fpub structType>,
+    /// Possibly qualified name of the extended struct.
+    pub base: Option<String>,
     pub polymorphic: Option<bool>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -217,7 +649,8 @@ pub struct FField {
     pub name: String,
     pub comment: Option<FAnnotationBlock>,
     pub r#type: FTypeRef,
-    pub array: Option<String>,
+    pub array: bool,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -225,17 +658,138 @@ pub struct FArrayType {
     pub name: String,
     pub comment: Option<FAnnotationBlock>,
     pub element_type: FTypeRef,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FAnnotationBlock {
     pub elements: Vec<FAnnotation>,
+    pub span: Span,
+}
+
+impl FAnnotationBlock {
+    /// Comments of all annotations of the given type in declaration order.
+    pub fn annotations<'a>(
+        &'a self,
+        r#type: &'a FAnnotationType,
+    ) -> impl Iterator<Item = &'a str> + 'a {
+        self.elements
+            .iter()
+            .filter(move |a| a.r#type.as_ref() == Some(r#type))
+            .map(|a| a.comment.as_deref().unwrap_or_default())
+    }
+
+    /// Comment of the first annotation of the given type.
+    pub fn annotation(&self, r#type: &FAnnotationType) -> Option<&str> {
+        self.elements
+            .iter()
+            .find(|a| a.r#type.as_ref() == Some(r#type))
+            .map(|a| a.comment.as_deref().unwrap_or_default())
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.annotation(&FAnnotationType::Description)
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        self.annotation(&FAnnotationType::Deprecated).is_some()
+    }
+}
+
+/// Model elements that may carry an annotation block.
+pub trait FAnnotated {
+    fn annotation_block(&self) -> Option<&FAnnotationBlock>;
+
+    /// Comment of the first annotation of the given type, e.g. `@owner` via
+    /// `FAnnotationType::Custom("owner".to_string())`.
+    fn annotation(&self, r#type: &FAnnotationType) -> Option<&str> {
+        self.annotation_block()
+            .and_then(|block| block.annotation(r#type))
+    }
+
+    /// Comment of the `@description` annotation.
+    fn description(&self) -> Option<&str> {
+        self.annotation_block()
+            .and_then(|block| block.description())
+    }
+
+    /// Whether the element is annotated with `@deprecated`.
+    fn is_deprecated(&self) -> bool {
+        self.annotation_block()
+            .is_some_and(|block| block.is_deprecated())
+    }
+}
+
+macro_rules! impl_f_annotated {
+    ($($element:ty),*) => {
+        $(
+            impl FAnnotated for $element {
+                fn annotation_block(&self) -> Option<&FAnnotationBlock> {
+                    self.comment.as_ref()
+                }
+            }
+        )*
+    };
+}
+
+impl_f_annotated!(
+    FTypeCollection,
+    FInterface,
+    FBroadcast,
+    FArgument,
+    FMethod,
+    FAttribute,
+    FMapType,
+    FEnumerationType,
+    FEnumerator,
+    FTypeDef,
+    FUnionType,
+    FStructType,
+    FField,
+    FArrayType,
+    FConstantDef
+);
+
+impl FAnnotated for FType {
+    fn annotation_block(&self) -> Option<&FAnnotationBlock> {
+        self.comment()
+    }
 }
 
+/// Model elements declared with a type reference, e.g. `UInt8 value` or `UInt8[] values`.
+///
+/// Array-ness is either implicit via the `[]` suffix or explicit by referencing
+/// a named `array Values of UInt8` definition, see [`FModel::element_type`].
+pub trait FTypedElement {
+    fn type_ref(&self) -> &FTypeRef;
+
+    /// Whether the element is declared with the `[]` suffix.
+    fn is_implicit_array(&self) -> bool;
+}
+
+macro_rules! impl_f_typed_element {
+    ($($element:ty),*) => {
+        $(
+            impl FTypedElement for $element {
+                fn type_ref(&self) -> &FTypeRef {
+                    &self.r#type
+                }
+
+                fn is_implicit_array(&self) -> bool {
+                    self.array
+                }
+            }
+        )*
+    };
+}
+
+impl_f_typed_element!(FArgument, FAttribute, FField, FDeclaration, FConstantDef);
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FAnnotation {
     pub r#type: Option<FAnnotationType>,
     pub comment: Option<String>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -251,35 +805,45 @@ pub enum FAnnotationType {
     SourceUri,
     SourceAlias,
     Details,
+    /// Any tag not predefined by Franca, e.g. `@owner:`, without the leading `@`.
+    Custom(String),
 }
 
-// // https://github.com/franca/franca/blob/108a9358c466bb14499d263ed6862c6b649d8c70/plugins/org.franca.deploymodel.dsl/src/org/franca/deploymodel/dsl/FDeploy.xtext
-// /// 6.1.2 Deployment properties
-// pub struct FDeployment {
-//     /// Properties related to whole Franca IDL interfaces (but not its instances)
-//     interfaces: (),
-//     /// Properties related to Franca type collections
-//     type_collections: (),
-//     /// Properties related to attributes of a Franca IDL interface
-//     attributes: (),
-//     /// Properties related to methods of a Franca IDL interface
-//     methods: (),
-//     broadcasts: (),
-//     arguments: (),
-//     strings: (),
-//     integers: (),
-//     floats: (),
-//     numbers: (),
-//     booleans: (),
-//     byte_buffers: (),
-//     structs: (),
-//     struct_fields: (),
-//     unions: (),
-//     union_fields: (),
-//     enumerations: (),
-//     enumerators: (),
-//     arrays: (),
-//     typedefs: (),
-//     providers: (),
-//     instances: (),
-// }
+impl FAnnotationType {
+    /// Maps an annotation tag without the leading `@` to its type,
+    /// unknown tags are kept as [`FAnnotationType::Custom`].
+    pub fn from_tag(tag: &str) -> Self {
+        match tag {
+            "description" => FAnnotationType::Description,
+            "author" => FAnnotationType::Author,
+            "experimental" => FAnnotationType::Experimental,
+            "deprecated" => FAnnotationType::Deprecated,
+            "see" => FAnnotationType::See,
+            "param" => FAnnotationType::Param,
+            "high-volume" => FAnnotationType::HighVolume,
+            "high-frequency" => FAnnotationType::HighFrequency,
+            "source-uri" => FAnnotationType::SourceUri,
+            "source-alias" => FAnnotationType::SourceAlias,
+            "details" => FAnnotationType::Details,
+            custom => FAnnotationType::Custom(custom.to_string()),
+        }
+    }
+
+    /// Annotation tag without the leading `@`.
+    pub fn tag(&self) -> &str {
+        match self {
+            FAnnotationType::Description => "description",
+            FAnnotationType::Author => "author",
+            FAnnotationType::Experimental => "experimental",
+            FAnnotationType::Deprecated => "deprecated",
+            FAnnotationType::See => "see",
+            FAnnotationType::Param => "param",
+            FAnnotationType::HighVolume => "high-volume",
+            FAnnotationType::HighFrequency => "high-frequency",
+            FAnnotationType::SourceUri => "source-uri",
+            FAnnotationType::SourceAlias => "source-alias",
+            FAnnotationType::Details => "details",
+            FAnnotationType::Custom(tag) => tag,
+        }
+    }
+}
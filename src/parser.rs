@@ -1,24 +1,46 @@
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take_till, take_till1},
-    character::complete::{self, char, line_ending, multispace0, multispace1, space0, space1},
-    combinator::{opt, value},
-    multi::many0,
-    AsChar, IResult,
+    bytes::complete::{
+        escaped_transform, is_not, take_till, take_till1, take_until, take_while, take_while1,
+    },
+    character::complete::{
+        self, anychar, char, digit1, hex_digit1, multispace0, multispace1, not_line_ending, one_of,
+        satisfy, space0,
+    },
+    combinator::{all_consuming, cut, eof, map, map_res, not, opt, peek, recognize, value},
+    error::{context, ParseError as _},
+    multi::{many0, many1, separated_list1},
+    sequence::{delimited, pair, preceded, terminated, tuple},
+    AsChar, InputTake,
 };
 
+use nom_locate::LocatedSpan;
+
+use crate::error::{Error, Expected, ParseError};
 use crate::{
-    FArgument, FBasicTypeId, FBroadcast, FInterface, FMethod, FModel, FTypeRef, FVersion, Import,
+    FAnnotation, FAnnotationBlock, FAnnotationType, FArgument, FArrayType, FAssignmentOp,
+    FAttribute, FBasicTypeId, FBlockExpression, FBroadcast, FConstant, FConstantDef, FContract,
+    FDeclaration, FElementInitializer, FEnumerationType, FEnumerator, FEventOnIf, FExpression,
+    FField, FFieldInitializer, FGuard, FInitializerExpression, FIntegerInterval, FInterface,
+    FMapType, FMethod, FModel, FOperator, FQualifiedElementRef, FState, FStateGraph, FStatement,
+    FStructType, FTransition, FTrigger, FType, FTypeCollection, FTypeDef, FTypeRef, FUnionType,
+    FVersion, Import, Span,
 };
 
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use crate::{
-        FArgument, FBasicTypeId, FBroadcast, FInterface, FMethod, FModel, FTypeRef, FVersion,
-        Import,
+        FAnnotationBlock, FAnnotationType, FArgument, FBasicTypeId, FEnumerationType, FField,
+        FIntegerInterval, FModel, FType,
+    };
+    use crate::{
+        FAssignmentOp, FBlockExpression, FConstant, FEventOnIf, FExpression, FOperator,
+        FQualifiedElementRef, FStatement,
     };
 
     use super::*;
+    use crate::FAnnotated;
 
     #[test]
     pub fn f_model_test() {
@@ -40,398 +62,2501 @@ interface HelloWorld {
 }
 ";
 
-        let in_args = vec![FArgument {
-            name: "name".to_string(),
-            comment: None,
-            r#type: FTypeRef {
-                predefined: Some(FBasicTypeId::String),
-            },
-            array: None,
-        }];
-
-        let out_args = vec![
-            FArgument {
-                name: "value_a".to_string(),
-                comment: None,
-                r#type: FTypeRef {
-                    predefined: Some(FBasicTypeId::String),
-                },
-                array: None,
-            },
-            FArgument {
-                name: "value_b".to_string(),
-                comment: None,
-                r#type: FTypeRef {
-                    predefined: Some(FBasicTypeId::Double),
-                },
-                array: None,
-            },
-            FArgument {
-                name: "value_c".to_string(),
-                comment: None,
-                r#type: FTypeRef {
-                    predefined: Some(FBasicTypeId::UInt32),
-                },
-                array: None,
-            },
-        ];
+        let (_, model) = f_model(def.into()).unwrap();
+        assert_eq!(model.name, "test");
+        assert!(model.imports.is_empty());
+        assert!(model.type_collections.is_empty());
 
-        let method = FMethod {
-            name: "sayHello".to_string(),
-            comment: None,
-            fire_and_forget: None,
-            in_args,
-            out_args,
-            error_enum: None,
-            errors: None,
-        };
+        let interface = &model.interfaces[0];
+        assert_eq!(interface.name, "HelloWorld");
+        let version = interface.version.unwrap();
+        assert_eq!((version.major, version.minor), (1, 0));
+        assert!(interface.attributes.is_empty());
+        assert!(interface.broadcasts.is_empty());
 
-        let interface = FInterface {
-            name: "HelloWorld".to_string(),
-            comment: None,
-            version: Some(FVersion { major: 1, minor: 0 }),
-            attributes: Vec::new(),
-            methods: vec![method],
-            broadcasts: Vec::new(),
+        let method = &interface.methods[0];
+        assert_eq!(method.name, "sayHello");
+        assert_eq!(arguments(&method.in_args), [("name", FBasicTypeId::String)]);
+        assert_eq!(
+            arguments(&method.out_args),
+            [
+                ("value_a", FBasicTypeId::String),
+                ("value_b", FBasicTypeId::Double),
+                ("value_c", FBasicTypeId::UInt32),
+            ]
+        );
+    }
+
+    /// Names and predefined types of `arguments`.
+    fn arguments(arguments: &[FArgument]) -> Vec<(&str, FBasicTypeId)> {
+        arguments
+            .iter()
+            .map(|a| (a.name.as_str(), a.r#type.predefined.unwrap()))
+            .collect()
+    }
+
+    #[test]
+    pub fn f_method_test() {
+        let def = "method sayHello {
+        in {
+            String name
+        }
+        out {
+            String value_a
+            Double value_b
+            UInt32 value_c
+        }
+    }
+}
+";
+
+        let (_, method) = f_method(def.into()).unwrap();
+        assert_eq!(method.name, "sayHello");
+        assert_eq!(method.selector, None);
+        assert_eq!(method.fire_and_forget, None);
+        assert_eq!(arguments(&method.in_args), [("name", FBasicTypeId::String)]);
+        assert_eq!(
+            arguments(&method.out_args),
+            [
+                ("value_a", FBasicTypeId::String),
+                ("value_b", FBasicTypeId::Double),
+                ("value_c", FBasicTypeId::UInt32),
+            ]
+        );
+        assert!(method.in_args.iter().all(|a| !a.array));
+    }
+
+    #[test]
+    pub fn f_version_test() {
+        let def = "version { major 1 minor 0 }\n";
+
+        let (_, version) = f_version(def.into()).unwrap();
+        assert_eq!((version.major, version.minor), (1, 0));
+    }
+
+    #[test]
+    pub fn f_broadcast_test() {
+        let def = "broadcast Greeting {
+    out {
+        String message
+    }
+}
+";
+
+        let (_, broadcast) = f_broadcast(def.into()).unwrap();
+        assert_eq!(broadcast.name, "Greeting");
+        assert_eq!(broadcast.selective, None);
+        assert_eq!(
+            arguments(&broadcast.out_args),
+            [("message", FBasicTypeId::String)]
+        );
+    }
+
+    #[test]
+    pub fn f_attribute_test() {
+        let def = "attribute UInt32 speed readonly noSubscriptions\n";
+
+        let (_, attribute) = f_attribute(def.into()).unwrap();
+        assert_eq!(attribute.name, "speed");
+        assert_eq!(attribute.r#type.predefined, Some(FBasicTypeId::UInt32));
+        assert_eq!(attribute.r#type.derived, None);
+        assert!(!attribute.array);
+        assert_eq!(attribute.readonly, Some(true));
+        assert_eq!(attribute.no_subscriptions, Some(true));
+    }
+
+    #[test]
+    pub fn f_attribute_array_annotated_test() {
+        let def = "<** @description: Raw frame payload
+    @author: someone **>
+    attribute UInt8[] data\n";
+
+        let (_, attribute) = f_attribute(def.into()).unwrap();
+        assert_eq!(attribute.name, "data");
+        assert_eq!(attribute.r#type.predefined, Some(FBasicTypeId::UInt8));
+        assert!(attribute.array);
+        assert_eq!(attribute.readonly, None);
+        assert_eq!(attribute.no_subscriptions, None);
+        assert_eq!(
+            annotations(attribute.comment.as_ref()),
+            [
+                (FAnnotationType::Description, "Raw frame payload"),
+                (FAnnotationType::Author, "someone"),
+            ]
+        );
+
+        let (_, attribute) = f_attribute("attribute Position pos readonly\n".into()).unwrap();
+        assert_eq!(attribute.name, "pos");
+        assert_eq!(attribute.r#type.derived.as_deref(), Some("Position"));
+        assert_eq!(attribute.readonly, Some(true));
+    }
+
+    /// Types and comments of the annotations in `block`.
+    fn annotations(block: Option<&FAnnotationBlock>) -> Vec<(FAnnotationType, &str)> {
+        block
+            .map_or(&[][..], |b| &b.elements)
+            .iter()
+            .map(|a| (a.r#type.clone().unwrap(), a.comment.as_deref().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    pub fn f_argument_array_test() {
+        let (_, argument) = f_argument("UInt8[] payload\n".into()).unwrap();
+        assert_eq!(argument.name, "payload");
+        assert_eq!(argument.r#type.predefined, Some(FBasicTypeId::UInt8));
+        assert!(argument.array);
+
+        let (_, argument) = f_argument("Payload payload\n".into()).unwrap();
+        assert_eq!(argument.r#type.derived.as_deref(), Some("Payload"));
+        assert!(!argument.array);
+
+        let (_, type_def) = f_type_def("typedef Bytes is UInt8[]".into()).unwrap();
+        assert!(type_def.array);
+    }
+
+    #[test]
+    pub fn f_type_ref_derived_test() {
+        let (_, type_ref) = f_type_ref("Position".into()).unwrap();
+        assert_eq!(type_ref.predefined, None);
+        assert_eq!(type_ref.derived.as_deref(), Some("Position"));
+
+        let (_, type_ref) = f_type_ref("common.Types.Position".into()).unwrap();
+        assert_eq!(type_ref.derived.as_deref(), Some("common.Types.Position"));
+
+        let (_, type_ref) = f_type_ref("UInt16".into()).unwrap();
+        assert_eq!(type_ref.predefined, Some(FBasicTypeId::UInt16));
+        assert_eq!(type_ref.derived, None);
+
+        // names merely starting with a basic type are derived
+        let (_, type_ref) = f_type_ref("StringList".into()).unwrap();
+        assert_eq!(type_ref.predefined, None);
+        assert_eq!(type_ref.derived.as_deref(), Some("StringList"));
+    }
+
+    #[test]
+    pub fn f_interface_members_any_order_test() {
+        let def = "interface Vehicle {
+    version { major 2 minor 1 }
+
+    broadcast Alarm {
+        out {
+            UInt8 level
+        }
+    }
+    attribute UInt32 speed readonly
+    method stop {
+        in {
+            Boolean force
+        }
+        out {
+            Boolean stopped
+        }
+    }
+    attribute Boolean engineOn
+}
+";
+
+        let (_, interface) = f_interface(def.into()).unwrap();
+        let attributes: Vec<_> = interface
+            .attributes
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect();
+        assert_eq!(attributes, ["speed", "engineOn"]);
+        assert_eq!(interface.methods.len(), 1);
+        assert_eq!(interface.broadcasts.len(), 1);
+    }
+
+    #[test]
+    pub fn f_type_collection_test() {
+        let def = "<** @description: Shared data types **>
+typeCollection CommonTypes {
+    version { major 1 minor 2 }
+}
+";
+
+        let (_, type_collection) = f_type_collection(def.into()).unwrap();
+        assert_eq!(type_collection.name.as_deref(), Some("CommonTypes"));
+        assert_eq!(
+            annotations(type_collection.comment.as_ref()),
+            [(FAnnotationType::Description, "Shared data types")]
+        );
+        let version = type_collection.version.unwrap();
+        assert_eq!((version.major, version.minor), (1, 2));
+        assert!(type_collection.types.is_empty());
+        assert!(type_collection.constants.is_empty());
+    }
+
+    #[test]
+    pub fn f_model_anonymous_type_collection_test() {
+        let def = "package test
+
+typeCollection {
+}
+
+interface Empty {
+}
+
+typeCollection Named {
+    version { major 1 minor 0 }
+}
+";
+
+        let (_, model) = f_model(def.into()).unwrap();
+        let names: Vec<_> = model
+            .type_collections
+            .iter()
+            .map(|t| t.name.clone())
+            .collect();
+        assert_eq!(names, [None, Some("Named".to_string())]);
+        assert_eq!(model.interfaces.len(), 1);
+    }
+
+    #[test]
+    pub fn f_type_collection_types_test() {
+        let def = "typeCollection CommonTypes {
+    version { major 1 minor 0 }
+
+    array Payload of UInt8
+
+    enumeration Gear {
+        Park
+        Reverse = 2,
+        Drive
+    }
+
+    struct Position {
+        Double latitude
+        Double longitude
+    }
+
+    union Value {
+        UInt32 number
+        String[] texts
+    }
+
+    map Settings { String to UInt32[] }
+
+    typedef Timestamp is UInt64
+}
+";
+
+        let (_, type_collection) = f_type_collection(def.into()).unwrap();
+        let names: Vec<_> = type_collection.types.iter().map(|t| t.name()).collect();
+        assert_eq!(
+            names,
+            [
+                "Payload",
+                "Gear",
+                "Position",
+                "Value",
+                "Settings",
+                "Timestamp"
+            ]
+        );
+
+        let fields = |fields: &[FField]| -> Vec<(String, FBasicTypeId, bool)> {
+            fields
+                .iter()
+                .map(|f| (f.name.clone(), f.r#type.predefined.unwrap(), f.array))
+                .collect()
         };
 
-        let exp_model = FModel {
-            name: "test".to_string(),
-            imports: Vec::new(),
-            interfaces: vec![interface],
-            type_collections: Vec::new(),
+        match &type_collection.types[..] {
+            [FType::Array(payload), FType::Enumeration(gear), FType::Struct(position), FType::Union(value), FType::Map(settings), FType::TypeDef(timestamp)] =>
+            {
+                assert_eq!(payload.element_type.predefined, Some(FBasicTypeId::UInt8));
+
+                let enumerators: Vec<_> = gear
+                    .enumerators
+                    .iter()
+                    .map(|e| (e.name.as_str(), e.value.clone()))
+                    .collect();
+                assert_eq!(
+                    enumerators,
+                    [
+                        ("Park", None),
+                        (
+                            "Reverse",
+                            Some(FExpression::Constant(FConstant::Integer(2)))
+                        ),
+                        ("Drive", None),
+                    ]
+                );
+                assert_eq!(gear.base, None);
+
+                assert_eq!(
+                    fields(&position.elements),
+                    [
+                        ("latitude".to_string(), FBasicTypeId::Double, false),
+                        ("longitude".to_string(), FBasicTypeId::Double, false),
+                    ]
+                );
+                assert_eq!(position.polymorphic, None);
+
+                assert_eq!(
+                    fields(&value.elements),
+                    [
+                        ("number".to_string(), FBasicTypeId::UInt32, false),
+                        ("texts".to_string(), FBasicTypeId::String, true),
+                    ]
+                );
+
+                assert_eq!(settings.key_type.predefined, Some(FBasicTypeId::String));
+                assert!(!settings.key_array);
+                assert_eq!(settings.value_type.predefined, Some(FBasicTypeId::UInt32));
+                assert!(settings.value_array);
+
+                assert_eq!(timestamp.actual_type.predefined, Some(FBasicTypeId::UInt64));
+                assert!(!timestamp.array);
+            }
+            types => panic!("unexpected types {:?}", types),
+        }
+    }
+
+    #[test]
+    pub fn f_interface_types_test() {
+        let def = "interface Navigation {
+    <** @description: Route status **>
+    enumeration Status { Idle, Routing, Arrived }
+
+    attribute Status status readonly
+
+    struct Route {
+        UInt32 id
+    }
+}
+";
+
+        let (_, interface) = f_interface(def.into()).unwrap();
+        let names: Vec<_> = interface.types.iter().map(|t| t.name()).collect();
+        assert_eq!(names, ["Status", "Route"]);
+        assert!(interface.types[0].comment().is_some());
+        assert_eq!(interface.attributes.len(), 1);
+    }
+
+    #[test]
+    pub fn f_type_ref_integer_interval_test() {
+        let (_, type_ref) = f_type_ref("Integer(0, 100)".into()).unwrap();
+        assert_eq!(type_ref.predefined, Some(FBasicTypeId::Integer));
+        assert_eq!(type_ref.derived, None);
+        assert_eq!(
+            type_ref.interval,
+            Some(FIntegerInterval {
+                lower_bound: Some(0),
+                upper_bound: Some(100),
+            })
+        );
+
+        let interval = |def: &str| f_type_ref(def.into()).unwrap().1.interval.unwrap();
+        assert_eq!(
+            interval("Integer(0, 100)").narrowest_type(),
+            FBasicTypeId::UInt8
+        );
+        assert_eq!(
+            interval("Integer(-5,5)").narrowest_type(),
+            FBasicTypeId::Int8
+        );
+        assert_eq!(
+            interval("Integer( 0 , 70000 )").narrowest_type(),
+            FBasicTypeId::UInt32
+        );
+        assert_eq!(
+            interval("Integer(minInt, 10)").narrowest_type(),
+            FBasicTypeId::Int64
+        );
+        assert_eq!(
+            interval("Integer(0, maxInt)").narrowest_type(),
+            FBasicTypeId::UInt64
+        );
+
+        let open = interval("Integer(minInt, maxInt)");
+        assert_eq!(open.lower_bound, None);
+        assert_eq!(open.upper_bound, None);
+        assert!(open.contains(i64::MIN.into()));
+        assert_eq!(
+            interval("Integer(0, 18446744073709551615)").narrowest_type(),
+            FBasicTypeId::UInt64
+        );
+        assert_eq!(
+            interval("Integer(0, 18446744073709551616)").narrowest_type(),
+            FBasicTypeId::Int64
+        );
+        assert!(!interval("Integer(-5,5)").contains(6));
+
+        let hex = interval("Integer(0, 0xFF)");
+        assert_eq!(hex.upper_bound, Some(255));
+        assert_eq!(hex.narrowest_type(), FBasicTypeId::UInt8);
+        let signed = interval("Integer(-0x80, 0b1111111)");
+        assert_eq!(signed.lower_bound, Some(-128));
+        assert_eq!(signed.upper_bound, Some(127));
+        assert_eq!(signed.narrowest_type(), FBasicTypeId::Int8);
+
+        let (_, type_ref) = f_type_ref("Integer".into()).unwrap();
+        assert_eq!(type_ref.predefined, Some(FBasicTypeId::Integer));
+        assert_eq!(type_ref.interval, None);
+    }
+
+    #[test]
+    pub fn f_model_comments_test() {
+        let def = "// Copyright header
+/* Multi line
+   block comment */
+package test // trailing
+
+<** @description: Greets the world
+    @author: franca **>
+interface HelloWorld { // interface comment
+    version { major 1 minor 0 }
+
+    /* before method */
+    <** @description: Say hello **>
+    method sayHello {
+        in {
+            <** @description: Who to greet **>
+            String name // trailing
+        }
+        out {
+            String value_a
+        }
+    } // after method
+
+    <** @deprecated: use Greeting2 **>
+    broadcast Greeting {
+        out {
+            String message
+        }
+    }
+}
+";
+
+        let (_, model) = f_model(def.into()).unwrap();
+        let interface = &model.interfaces[0];
+        assert_eq!(
+            annotations(interface.comment.as_ref()),
+            [
+                (FAnnotationType::Description, "Greets the world"),
+                (FAnnotationType::Author, "franca"),
+            ]
+        );
+
+        let method = &interface.methods[0];
+        assert_eq!(
+            annotations(method.comment.as_ref()),
+            [(FAnnotationType::Description, "Say hello")]
+        );
+        assert_eq!(
+            annotations(method.in_args[0].comment.as_ref()),
+            [(FAnnotationType::Description, "Who to greet")]
+        );
+        assert_eq!(method.out_args[0].comment, None);
+
+        let broadcast = &interface.broadcasts[0];
+        assert_eq!(
+            broadcast.comment.as_ref().unwrap().elements[0].r#type,
+            Some(FAnnotationType::Deprecated)
+        );
+    }
+
+    #[test]
+    pub fn f_annotation_text_test() {
+        let def = "<** @author: jane@example.com
+    @description: a * b ** c, see @param
+    @see: \\@escaped \\**> **>";
+
+        let (_, block) = f_annotation_block(def.into()).unwrap();
+        assert_eq!(
+            annotations(Some(&block)),
+            [
+                (FAnnotationType::Author, "jane@example.com"),
+                (FAnnotationType::Description, "a * b ** c, see @param"),
+                (FAnnotationType::See, "@escaped **>"),
+            ]
+        );
+
+        let (_, block) = f_annotation_block("<** @description: * **>".into()).unwrap();
+        assert_eq!(block.description(), Some("*"));
+
+        let (_, block) = f_annotation_block("<** @deprecated: **>".into()).unwrap();
+        assert_eq!(block.elements[0].comment, None);
+        assert!(block.is_deprecated());
+    }
+
+    #[test]
+    pub fn f_annotation_custom_test() {
+        let def = "<** @description: Unlock the doors
+    @security-level: high
+    @owner: body-team
+    @securityLevel: low
+    @owner2: chassis_team
+    @deprecated: use unlockAll **>
+method unlock {
+    in {
+        Boolean force
+    }
+    out {
+        Boolean unlocked
+    }
+}
+";
+
+        let (_, method) = f_method(def.into()).unwrap();
+        let security_level = FAnnotationType::Custom("security-level".to_string());
+        let owner = FAnnotationType::Custom("owner".to_string());
+
+        assert_eq!(method.description(), Some("Unlock the doors"));
+        assert_eq!(method.annotation(&security_level), Some("high"));
+        assert_eq!(method.annotation(&owner), Some("body-team"));
+        assert_eq!(security_level.tag(), "security-level");
+        assert_eq!(
+            method.annotation(&FAnnotationType::Custom("securityLevel".to_string())),
+            Some("low")
+        );
+        assert_eq!(
+            method.annotation(&FAnnotationType::Custom("owner2".to_string())),
+            Some("chassis_team")
+        );
+        assert!(method.is_deprecated());
+
+        let argument = &method.in_args[0];
+        assert_eq!(argument.description(), None);
+        assert!(!argument.is_deprecated());
+    }
+
+    #[test]
+    pub fn f_method_fire_and_forget_test() {
+        let def = "method notify fireAndForget {
+    in {
+        UInt8 level
+    }
+}
+";
+
+        let (_, method) = f_method(def.into()).unwrap();
+        assert_eq!(method.fire_and_forget, Some(true));
+        assert_eq!(method.in_args.len(), 1);
+        assert!(method.out_args.is_empty());
+
+        let (_, method) = f_method("method ping {\n}\n".into()).unwrap();
+        assert_eq!(method.fire_and_forget, None);
+        assert!(method.in_args.is_empty());
+        assert!(method.out_args.is_empty());
+
+        let def = "method status {
+    out {
+        Boolean ok
+    }
+}
+";
+        let (_, method) = f_method(def.into()).unwrap();
+        assert!(method.in_args.is_empty());
+        assert_eq!(method.out_args.len(), 1);
+
+        let def = "package test
+
+interface Notifier {
+    method notify fireAndForget {
+        in {
+            UInt8 level
+        }
+        out {
+            Boolean ok
+        }
+    }
+}
+";
+        let error = parse_fidl(def).unwrap_err();
+        assert_eq!((error.line, error.column), (8, 9));
+        assert_eq!(error.found.as_deref(), Some("out"));
+        assert!(error.expected.contains(&"`}`".to_string()));
+        assert_eq!(error.context, ["interface Notifier", "method notify"]);
+
+        let def = "package test
+
+interface Notifier {
+    method m fireAndForget {
+        error {
+            A
+        }
+    }
+}
+";
+        let error = parse_fidl(def).unwrap_err();
+        assert_eq!((error.line, error.column), (5, 9));
+        assert_eq!(error.found.as_deref(), Some("error"));
+        assert!(error.expected.contains(&"`}`".to_string()));
+        assert_eq!(error.context, ["interface Notifier", "method m"]);
+    }
+
+    #[test]
+    pub fn f_method_errors_test() {
+        let def = "method open {
+    in {
+        String path
+    }
+    error common.FileError
+}
+";
+        let (_, method) = f_method(def.into()).unwrap();
+        assert_eq!(method.error_enum.as_deref(), Some("common.FileError"));
+        assert_eq!(method.errors, None);
+
+        let def = "method close {
+    error {
+        NotOpen
+        Busy = 3
+    }
+}
+";
+        let enumerators = |errors: &FEnumerationType| -> Vec<(String, Option<FExpression>)> {
+            errors
+                .enumerators
+                .iter()
+                .map(|e| (e.name.clone(), e.value.clone()))
+                .collect()
         };
 
-        let (_, model) = f_model(def).unwrap();
-        assert_eq!(model, exp_model);
-    }
+        let (_, method) = f_method(def.into()).unwrap();
+        assert_eq!(method.error_enum, None);
+        let errors = method.errors.unwrap();
+        assert_eq!(errors.name, "");
+        assert_eq!(errors.base, None);
+        assert_eq!(
+            enumerators(&errors),
+            [
+                ("NotOpen".to_string(), None),
+                (
+                    "Busy".to_string(),
+                    Some(FExpression::Constant(FConstant::Integer(3)))
+                ),
+            ]
+        );
+
+        let def = "method remove {
+    out {
+        Boolean removed
+    }
+    error extends common.FileError { ReadOnly }
+}
+";
+        let (_, method) = f_method(def.into()).unwrap();
+        let errors = method.errors.unwrap();
+        assert_eq!(errors.base.as_deref(), Some("common.FileError"));
+        assert_eq!(enumerators(&errors), [("ReadOnly".to_string(), None)]);
+    }
+
+    #[test]
+    pub fn f_selector_test() {
+        let def = "broadcast changed:v2 selective {
+    out {
+        UInt32 value
+    }
+}
+";
+        let (_, broadcast) = f_broadcast(def.into()).unwrap();
+        assert_eq!(broadcast.name, "changed");
+        assert_eq!(broadcast.selector.as_deref(), Some("v2"));
+        assert_eq!(broadcast.selective, Some(true));
+        assert_eq!(broadcast.name_with_selector(), "changed:v2");
+
+        let def = "interface Overloads {
+    method foo {
+        in {
+            UInt8 a
+        }
+    }
+    method foo:v2 fireAndForget {
+        in {
+            UInt16 a
+        }
+    }
+}
+";
+        let (_, interface) = f_interface(def.into()).unwrap();
+        let names: Vec<_> = interface
+            .methods
+            .iter()
+            .map(|m| m.name_with_selector())
+            .collect();
+        assert_eq!(names, ["foo", "foo:v2"]);
+        assert_eq!(interface.methods[1].fire_and_forget, Some(true));
+    }
+
+    #[test]
+    pub fn f_contract_test() {
+        let def = "interface Player {
+    method play {
+    }
+    method stop {
+    }
+    broadcast finished {
+        out {
+            UInt32 track
+        }
+    }
+
+    contract {
+        vars {
+            UInt32 plays = 0
+            Boolean[] history
+        }
+        PSM {
+            initial idle
+            state idle {
+                on call play [plays < 10 && !muted] -> playing { plays += 1 }
+            }
+            state playing {
+                on signal finished -> idle
+                on call stop -> idle {
+                    if (plays >= 3) history = true else { plays = 0 }
+                }
+            }
+        }
+    }
+}
+";
+
+        let (_, interface) = f_interface(def.into()).unwrap();
+        let contract = interface.contract.unwrap();
+
+        assert_eq!(contract.variables.len(), 2);
+        assert_eq!(
+            contract.variables[0].rhs,
+            Some(FInitializerExpression::Expression(FExpression::Constant(
+                FConstant::Integer(0)
+            )))
+        );
+        assert!(contract.variables[1].array);
+
+        let graph = &contract.state_graph;
+        assert_eq!(graph.initial, "idle");
+        assert_eq!(graph.states.len(), 2);
+
+        let element = |name: &str| {
+            Box::new(FExpression::ElementRef(FQualifiedElementRef {
+                element: name.to_string(),
+                fields: Vec::new(),
+            }))
+        };
+
+        let play = graph
+            .step("idle", &FEventOnIf::Call("play".to_string()))
+            .unwrap();
+        assert_eq!(play.to, "playing");
+        assert_eq!(
+            play.guard.as_ref().unwrap().condition,
+            FExpression::Binary {
+                left: Box::new(FExpression::Binary {
+                    left: element("plays"),
+                    op: FOperator::Smaller,
+                    right: Box::new(FExpression::Constant(FConstant::Integer(10))),
+                }),
+                op: FOperator::And,
+                right: Box::new(FExpression::Unary {
+                    op: FOperator::Not,
+                    operand: element("muted"),
+                }),
+            }
+        );
+        assert_eq!(
+            play.action,
+            Some(FBlockExpression {
+                statements: vec![FStatement::Assignment {
+                    lhs: FQualifiedElementRef {
+                        element: "plays".to_string(),
+                        fields: Vec::new(),
+                    },
+                    op: FAssignmentOp::AddAssign,
+                    rhs: FExpression::Constant(FConstant::Integer(1)),
+                }],
+            })
+        );
+
+        let finished = graph
+            .step("playing", &FEventOnIf::Signal("finished".to_string()))
+            .unwrap();
+        assert_eq!(finished.to, "idle");
+        assert_eq!(finished.action, None);
+
+        let stop = graph
+            .step("playing", &FEventOnIf::Call("stop".to_string()))
+            .unwrap();
+        let statements = &stop.action.as_ref().unwrap().statements;
+        assert!(matches!(
+            &statements[0],
+            FStatement::If {
+                r#else: Some(r#else),
+                ..
+            } if matches!(**r#else, FStatement::Block(_))
+        ));
+
+        assert!(graph
+            .step("idle", &FEventOnIf::Call("stop".to_string()))
+            .is_none());
+    }
+
+    #[test]
+    pub fn f_expression_test() {
+        let (_, expression) = f_expression("1 + 2 * -x->y == 3.5d || \"a\\\"b\"".into()).unwrap();
+
+        let constant = |c| Box::new(FExpression::Constant(c));
+        let exp_expression = FExpression::Binary {
+            left: Box::new(FExpression::Binary {
+                left: Box::new(FExpression::Binary {
+                    left: constant(FConstant::Integer(1)),
+                    op: FOperator::Addition,
+                    right: Box::new(FExpression::Binary {
+                        left: constant(FConstant::Integer(2)),
+                        op: FOperator::Multiplication,
+                        right: Box::new(FExpression::Unary {
+                            op: FOperator::Subtraction,
+                            operand: Box::new(FExpression::ElementRef(FQualifiedElementRef {
+                                element: "x".to_string(),
+                                fields: vec!["y".to_string()],
+                            })),
+                        }),
+                    }),
+                }),
+                op: FOperator::Equal,
+                right: constant(FConstant::Double("3.5".to_string())),
+            }),
+            op: FOperator::Or,
+            right: constant(FConstant::String("a\"b".to_string())),
+        };
+        assert_eq!(expression, exp_expression);
+
+        let (_, expression) = f_expression("(a - b) - c".into()).unwrap();
+        assert!(matches!(
+            expression,
+            FExpression::Binary { left, op: FOperator::Subtraction, .. }
+                if matches!(*left, FExpression::Binary { .. })
+        ));
+
+        let (_, expression) = f_expression("trueValue".into()).unwrap();
+        assert!(matches!(expression, FExpression::ElementRef(_)));
+
+        assert_eq!(integer("0x1F".into()).map(|(_, i)| i), Ok(31));
+        assert_eq!(integer("0B0101".into()).map(|(_, i)| i), Ok(5));
+        assert_eq!(integer("042".into()).map(|(_, i)| i), Ok(42));
+    }
+
+    #[test]
+    pub fn f_constant_def_test() {
+        let def = "typeCollection Constants {
+    const UInt32 MAX = 100
+    struct Point {
+        Int32 x
+        Int32 y
+    }
+    <** @description: Coordinate origin **>
+    const Point ORIGIN = { x: 0, y: -MAX }
+    const UInt8[] BYTES = [1, 2, 3,]
+    const Lookup NAMES = [\"one\" => 1, \"two\" => 2]
+}
+";
+
+        let (_, type_collection) = f_type_collection(def.into()).unwrap();
+        assert_eq!(type_collection.types.len(), 1);
+        assert_eq!(type_collection.constants.len(), 4);
+
+        let expression = |e| FInitializerExpression::Expression(e);
+        let integer = |i| expression(FExpression::Constant(FConstant::Integer(i)));
+        let string = |s: &str| expression(FExpression::Constant(FConstant::String(s.to_string())));
+
+        let max = &type_collection.constants[0];
+        assert_eq!(max.name, "MAX");
+        assert_eq!(max.r#type.predefined, Some(FBasicTypeId::UInt32));
+        assert!(!max.array);
+        assert_eq!(max.rhs, integer(100));
+
+        let origin = &type_collection.constants[1];
+        assert_eq!(origin.description(), Some("Coordinate origin"));
+        assert_eq!(origin.r#type.derived.as_deref(), Some("Point"));
+        assert_eq!(
+            origin.rhs,
+            FInitializerExpression::Compound(vec![
+                FFieldInitializer {
+                    element: "x".to_string(),
+                    value: integer(0),
+                },
+                FFieldInitializer {
+                    element: "y".to_string(),
+                    value: expression(FExpression::Unary {
+                        op: FOperator::Subtraction,
+                        operand: Box::new(FExpression::ElementRef(FQualifiedElementRef {
+                            element: "MAX".to_string(),
+                            fields: Vec::new(),
+                        })),
+                    }),
+                },
+            ])
+        );
+
+        let bytes = &type_collection.constants[2];
+        assert!(bytes.array);
+        assert_eq!(
+            bytes.rhs,
+            FInitializerExpression::Bracket(
+                (1..=3)
+                    .map(|i| FElementInitializer {
+                        first: integer(i),
+                        second: None,
+                    })
+                    .collect()
+            )
+        );
+
+        assert_eq!(
+            type_collection.constants[3].rhs,
+            FInitializerExpression::Bracket(vec![
+                FElementInitializer {
+                    first: string("one"),
+                    second: Some(integer(1)),
+                },
+                FElementInitializer {
+                    first: string("two"),
+                    second: Some(integer(2)),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    pub fn span_test() {
+        let def = "package test
+
+interface HelloWorld {
+    <** @description: Greets **>
+    method sayHello {
+        in {
+            String name
+        }
+    }
+}
+";
+        let model = parse_fidl(def).unwrap();
+        let interface = &model.interfaces[0];
+        assert_eq!((interface.span.line, interface.span.column), (3, 1));
+        assert_eq!(
+            (interface.span.end_line, interface.span.end_column),
+            (10, 2)
+        );
+
+        let method = &interface.methods[0];
+        assert_eq!((method.span.line, method.span.column), (4, 5));
+        assert_eq!((method.span.end_line, method.span.end_column), (9, 6));
+        assert!(def[method.span.start..method.span.end].starts_with("<** @description"));
+
+        let argument = &method.in_args[0];
+        assert_eq!(&def[argument.span.start..argument.span.end], "String name");
+        assert_eq!(
+            &def[argument.r#type.span.start..argument.r#type.span.end],
+            "String"
+        );
+    }
+
+    #[test]
+    pub fn span_position_test() {
+        let def = "package test
+
+typeCollection Types {
+    enumeration Gear { Park, Drive }
+    typedef Speed is UInt16
+    typedef Speed2 is UInt16
+}
+";
+        let model = parse_fidl(def).unwrap();
+        let types = &model.type_collections[0].types;
+
+        let gear = match &types[0] {
+            FType::Enumeration(gear) => gear,
+            other => panic!("unexpected type {:?}", other),
+        };
+        let position = |span: Span| (span.line, span.column, span.start);
+        assert_eq!(position(gear.span), (4, 5, 41));
+        assert_eq!(position(gear.enumerators[0].span), (4, 24, 60));
+        assert_eq!(position(gear.enumerators[1].span), (4, 30, 66));
+        assert_eq!(position(types[1].span()), (5, 5, 78));
+        assert_eq!(position(types[2].span()), (6, 5, 106));
+
+        // equally typed elements differ by their location only
+        assert_ne!(types[1].span(), types[2].span());
+        let (speed, speed2) = match (&types[1], &types[2]) {
+            (FType::TypeDef(speed), FType::TypeDef(speed2)) => (speed, speed2),
+            other => panic!("unexpected types {:?}", other),
+        };
+        assert_eq!(speed.actual_type.predefined, speed2.actual_type.predefined);
+        assert_ne!(speed.actual_type, speed2.actual_type);
+    }
+
+    #[test]
+    pub fn layout_test() {
+        let expanded = "package test
+
+interface HelloWorld {
+    version { major 1 minor 0 }
+
+    method sayHello {
+        in {
+            String name
+        }
+        out {
+            UInt8[] data
+        }
+    }
+
+    broadcast greeted {
+        out {
+            String name
+        }
+    }
+}
+";
+        let compact = "package test interface HelloWorld{version{major 1 minor 0}\
+method sayHello{in{String name}out{UInt8[]data}}broadcast greeted{out{String name}}}";
+        let crlf = expanded.replace('\n', "\r\n").replace("    ", "\t");
+        let commented = expanded.replace(' ', " /* c */ ").replace('\n', " // c\n");
+
+        let model = parse_fidl(expanded).unwrap();
+        assert_eq!(model.interfaces[0].methods[0].out_args.len(), 1);
+        let model = without_spans(&model);
+        assert_eq!(without_spans(&parse_fidl(compact).unwrap()), model);
+        assert_eq!(without_spans(&parse_fidl(&crlf).unwrap()), model);
+        assert_eq!(without_spans(&parse_fidl(&commented).unwrap()), model);
+    }
+
+    /// Debug representation of `model` without any spans, which differ between layouts.
+    fn without_spans(model: &FModel) -> String {
+        format!("{:#?}", model)
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("span: "))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    pub fn import_uri_test() {
+        let (_, ns) = imported_namespace(" org.franca*.examples.demo.* from".into()).unwrap();
+        assert_eq!(*ns.fragment(), "org.franca*.examples.demo.*");
+        let def = r##"import org.franca.examples.demo.* from "basic.fidl""##;
+
+        let (_, import) = import(def.into()).unwrap();
+        assert_eq!(
+            import.imported_namespace.as_deref(),
+            Some("org.franca.examples.demo.*")
+        );
+        assert_eq!(import.import_uri.as_deref(), Some("basic.fidl"));
+    }
+
+    #[test]
+    pub fn import_uri_test_without_from() {
+        let def = r##"import "platform:/plugin/abc.fdepl"""##;
+
+        let (_, import) = import(def.into()).unwrap();
+        assert_eq!(import.imported_namespace, None);
+        assert_eq!(
+            import.import_uri.as_deref(),
+            Some("platform:/plugin/abc.fdepl")
+        );
+    }
+}
+
+/// Parser input keeping track of its location within the parsed source.
+pub type Input<'a> = LocatedSpan<&'a str>;
+
+/// `nom::IResult` defaulting to the [`Error`] type of this module.
+pub type IResult<I, O, E = Error<I>> = nom::IResult<I, O, E>;
+
+/// Parses a `.fidl` file, errors are located by line and column within `s`.
+pub fn parse_fidl(s: &str) -> Result<FModel, ParseError> {
+    parse_complete(f_model, s)
+}
+
+/// Runs `parser` on the whole of `s`. Anything but whitespace and comments left
+/// over by `parser` is reported as an error rather than silently dropped.
+pub fn parse_complete<'a, O>(
+    parser: impl FnMut(Input<'a>) -> IResult<Input<'a>, O>,
+    s: &'a str,
+) -> Result<O, ParseError> {
+    let input = Input::new(s);
+    match terminated(parser, pair(sp, expect("end of input", eof)))(input) {
+        Ok((_, o)) => Ok(o),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(ParseError::new(s, e)),
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::new(
+            s,
+            Error::expected(input.take_split(s.len()).0, Expected::Named("more input")),
+        )),
+    }
+}
+
+/// Package, imports and model elements. Fails at the first construct that is not
+/// a model element, unless only whitespace and comments are left.
+pub fn f_model(s: Input) -> IResult<Input, FModel> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("package")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = e_string(s)?;
+
+    let (s, imports) = many0(element("import", import))(s)?;
+    let (s, (elements, _)) =
+        items_until(f_model_element, peek(pair(sp, expect("end of input", eof))))(s)?;
+
+    let mut interfaces = Vec::new();
+    let mut type_collections = Vec::new();
+    for element in elements {
+        match element {
+            FModelElement::Interface(interface) => interfaces.push(interface),
+            FModelElement::TypeCollection(type_collection) => {
+                type_collections.push(type_collection)
+            }
+        }
+    }
+
+    Ok((
+        s,
+        FModel {
+            name: name.to_string(),
+            imports,
+            interfaces,
+            type_collections,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// Top level elements that may appear in any order after the imports.
+#[allow(clippy::large_enum_variant)]
+enum FModelElement {
+    Interface(FInterface),
+    TypeCollection(FTypeCollection),
+}
+
+fn f_model_element(s: Input) -> IResult<Input, FModelElement> {
+    alt((
+        map(element("interface", f_interface), FModelElement::Interface),
+        map(
+            element("typeCollection", f_type_collection),
+            FModelElement::TypeCollection,
+        ),
+    ))(s)
+}
+
+/// e.g. `typeCollection CommonTypes { version { major 1 minor 0 } }`,
+/// the name may be omitted for anonymous type collections.
+pub fn f_type_collection(s: Input) -> IResult<Input, FTypeCollection> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, comment) = opt(f_annotation_block)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("typeCollection")(s)?;
+    let (s, name) = opt(preceded(sp, identifier))(s)?;
+
+    let (s, _) = brc_open(s)?;
+    let (s, version) = opt(element("version", f_version))(s)?;
+    let (s, (members, _)) = items_until(f_type_collection_member, brc_close)(s)?;
+
+    let mut types = Vec::new();
+    let mut constants = Vec::new();
+    for member in members {
+        match member {
+            FTypeCollectionMember::Type(r#type) => types.push(r#type),
+            FTypeCollectionMember::Constant(constant) => constants.push(constant),
+        }
+    }
+
+    Ok((
+        s,
+        FTypeCollection {
+            name: name.map(|n| n.to_string()),
+            comment,
+            version,
+            types,
+            constants,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// Elements that may appear in any order within a type collection body.
+enum FTypeCollectionMember {
+    Type(FType),
+    Constant(FConstantDef),
+}
+
+fn f_type_collection_member(s: Input) -> IResult<Input, FTypeCollectionMember> {
+    alt((
+        map(f_type, FTypeCollectionMember::Type),
+        map(
+            element("const", f_constant_def),
+            FTypeCollectionMember::Constant,
+        ),
+    ))(s)
+}
+
+pub fn f_interface(s: Input) -> IResult<Input, FInterface> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, comment) = opt(f_annotation_block)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("interface")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = identifier(s)?;
+    let (s, base) = opt(extends)(s)?;
+    let (s, managed_interfaces) = opt(preceded(
+        tuple((sp, keyword("manages"), sp)),
+        separated_list1(tuple((sp, char(','), sp)), fqn),
+    ))(s)?;
+
+    let (s, _) = brc_open(s)?;
+    let (s, version) = opt(element("version", f_version))(s)?;
+    let (s, (members, contract)) = items_until(
+        f_interface_member,
+        terminated(opt(element("contract", f_contract)), brc_close),
+    )(s)?;
+
+    let mut types = Vec::new();
+    let mut constants = Vec::new();
+    let mut attributes = Vec::new();
+    let mut methods = Vec::new();
+    let mut broadcasts = Vec::new();
+    for member in members {
+        match member {
+            FInterfaceMember::Type(r#type) => types.push(r#type),
+            FInterfaceMember::Constant(constant) => constants.push(constant),
+            FInterfaceMember::Attribute(attribute) => attributes.push(attribute),
+            FInterfaceMember::Method(method) => methods.push(method),
+            FInterfaceMember::Broadcast(broadcast) => broadcasts.push(broadcast),
+        }
+    }
+
+    Ok((
+        s,
+        FInterface {
+            name: name.to_string(),
+            comment,
+            version,
+            types,
+            constants,
+            attributes,
+            methods,
+            broadcasts,
+            contract,
+            base: base.map(|b| b.to_string()),
+            managed_interfaces: managed_interfaces
+                .unwrap_or_default()
+                .into_iter()
+                .map(|m| m.to_string())
+                .collect(),
+            span: span(start, s),
+        },
+    ))
+}
+
+/// Elements that may appear in any order within an interface body.
+enum FInterfaceMember {
+    Type(FType),
+    Constant(FConstantDef),
+    Attribute(FAttribute),
+    Method(FMethod),
+    Broadcast(FBroadcast),
+}
+
+fn f_interface_member(s: Input) -> IResult<Input, FInterfaceMember> {
+    alt((
+        map(f_type, FInterfaceMember::Type),
+        map(element("const", f_constant_def), FInterfaceMember::Constant),
+        map(
+            element("attribute", f_attribute),
+            FInterfaceMember::Attribute,
+        ),
+        map(element("method", f_method), FInterfaceMember::Method),
+        map(
+            element("broadcast", f_broadcast),
+            FInterfaceMember::Broadcast,
+        ),
+    ))(s)
+}
+
+pub fn f_type(s: Input) -> IResult<Input, FType> {
+    alt((
+        map(element("array", f_array_type), FType::Array),
+        map(
+            element("enumeration", f_enumeration_type),
+            FType::Enumeration,
+        ),
+        map(element("struct", f_struct_type), FType::Struct),
+        map(element("union", f_union_type), FType::Union),
+        map(element("map", f_map_type), FType::Map),
+        map(element("typedef", f_type_def), FType::TypeDef),
+    ))(s)
+}
+
+/// e.g. `array Payload of UInt8`
+pub fn f_array_type(s: Input) -> IResult<Input, FArrayType> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, comment) = opt(f_annotation_block)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("array")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = identifier(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("of")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, element_type) = f_type_ref(s)?;
+    Ok((
+        s,
+        FArrayType {
+            name: name.to_string(),
+            comment,
+            element_type,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// e.g. `enumeration Gear { Park Reverse = 2 Drive }`
+pub fn f_enumeration_type(s: Input) -> IResult<Input, FEnumerationType> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, comment) = opt(f_annotation_block)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("enumeration")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = identifier(s)?;
+    let (s, base) = opt(extends)(s)?;
+    let (s, enumerators) = f_enumerators(s)?;
+    Ok((
+        s,
+        FEnumerationType {
+            name: name.to_string(),
+            comment,
+            enumerators,
+            base: base.map(|b| b.to_string()),
+            span: span(start, s),
+        },
+    ))
+}
+
+/// Braced enumerator list of named and inline enumerations.
+fn f_enumerators(s: Input) -> IResult<Input, Vec<FEnumerator>> {
+    let (s, _) = brc_open(s)?;
+    let (s, (enumerators, _)) = items_until(f_enumerator, brc_close)(s)?;
+    Ok((s, enumerators))
+}
+
+/// Enumerators may optionally be separated by commas.
+pub fn f_enumerator(s: Input) -> IResult<Input, FEnumerator> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, comment) = opt(f_annotation_block)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = identifier(s)?;
+    let (s, value) = opt(preceded(tuple((sp, char('='), sp)), f_expression))(s)?;
+    let (s, _) = opt(preceded(sp, char(',')))(s)?;
+    Ok((
+        s,
+        FEnumerator {
+            name: name.to_string(),
+            comment,
+            value,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// e.g. `struct Position { Double latitude Double longitude }`,
+/// `struct Shape polymorphic { ... }` or `struct Circle extends Shape { ... }`
+pub fn f_struct_type(s: Input) -> IResult<Input, FStructType> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, comment) = opt(f_annotation_block)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("struct")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = identifier(s)?;
+    let (s, base) = opt(extends)(s)?;
+    let (s, polymorphic) = opt(preceded(sp, value(true, keyword("polymorphic"))))(s)?;
+    let (s, elements) = f_fields(s)?;
+    Ok((
+        s,
+        FStructType {
+            name: name.to_string(),
+            comment,
+            elements,
+            base: base.map(|b| b.to_string()),
+            polymorphic,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// e.g. `union Value { UInt32 number String text }`
+pub fn f_union_type(s: Input) -> IResult<Input, FUnionType> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, comment) = opt(f_annotation_block)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("union")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = identifier(s)?;
+    let (s, base) = opt(extends)(s)?;
+    let (s, elements) = f_fields(s)?;
+    Ok((
+        s,
+        FUnionType {
+            name: name.to_string(),
+            comment,
+            elements,
+            base: base.map(|b| b.to_string()),
+            span: span(start, s),
+        },
+    ))
+}
+
+/// Braced field list of structs and unions.
+fn f_fields(s: Input) -> IResult<Input, Vec<FField>> {
+    let (s, _) = brc_open(s)?;
+    let (s, (elements, _)) = items_until(f_field, brc_close)(s)?;
+    Ok((s, elements))
+}
+
+pub fn f_field(s: Input) -> IResult<Input, FField> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, comment) = opt(f_annotation_block)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, r#type) = f_type_ref(s)?;
+    let (s, array) = opt(implicit_array)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = identifier(s)?;
+    Ok((
+        s,
+        FField {
+            name: name.to_string(),
+            comment,
+            r#type,
+            array: array.is_some(),
+            span: span(start, s),
+        },
+    ))
+}
+
+/// e.g. `map Settings { String to UInt32 }`
+pub fn f_map_type(s: Input) -> IResult<Input, FMapType> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, comment) = opt(f_annotation_block)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("map")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = identifier(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, _) = sp(s)?;
+    let (s, key_type) = f_type_ref(s)?;
+    let (s, key_array) = opt(implicit_array)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("to")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, value_type) = f_type_ref(s)?;
+    let (s, value_array) = opt(implicit_array)(s)?;
+    let (s, _) = brc_close(s)?;
+    Ok((
+        s,
+        FMapType {
+            name: name.to_string(),
+            comment,
+            key_type,
+            key_array: key_array.is_some(),
+            value_type,
+            value_array: value_array.is_some(),
+            span: span(start, s),
+        },
+    ))
+}
+
+/// e.g. `typedef Timestamp is UInt64`
+pub fn f_type_def(s: Input) -> IResult<Input, FTypeDef> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, comment) = opt(f_annotation_block)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("typedef")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = identifier(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("is")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, actual_type) = f_type_ref(s)?;
+    let (s, array) = opt(implicit_array)(s)?;
+    Ok((
+        s,
+        FTypeDef {
+            name: name.to_string(),
+            comment,
+            actual_type,
+            array: array.is_some(),
+            span: span(start, s),
+        },
+    ))
+}
+
+/// e.g. `attribute UInt32 speed readonly noSubscriptions`
+pub fn f_attribute(s: Input) -> IResult<Input, FAttribute> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, comment) = opt(f_annotation_block)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("attribute")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, r#type) = f_type_ref(s)?;
+    let (s, array) = opt(implicit_array)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = identifier(s)?;
+    let (s, readonly) = opt(preceded(sp, value(true, keyword("readonly"))))(s)?;
+    let (s, no_subscriptions) = opt(preceded(sp, value(true, keyword("noSubscriptions"))))(s)?;
+    Ok((
+        s,
+        FAttribute {
+            name: name.to_string(),
+            comment,
+            r#type,
+            array: array.is_some(),
+            readonly,
+            no_subscriptions,
+            span: span(start, s),
+        },
+    ))
+}
+
+pub fn f_constant_def(s: Input) -> IResult<Input, FConstantDef> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, comment) = opt(f_annotation_block)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("const")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, r#type) = f_type_ref(s)?;
+    let (s, array) = opt(implicit_array)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = identifier(s)?;
+    let (s, _) = tuple((sp, char('='), sp))(s)?;
+    let (s, rhs) = f_initializer_expression(s)?;
+    Ok((
+        s,
+        FConstantDef {
+            name: name.to_string(),
+            comment,
+            r#type,
+            array: array.is_some(),
+            rhs,
+            span: span(start, s),
+        },
+    ))
+}
+
+/// Plain expression, `{ field: value, ... }` or `[element, key => value, ...]`
+pub fn f_initializer_expression(s: Input) -> IResult<Input, FInitializerExpression> {
+    alt((
+        map(
+            delimited(
+                pair(char('{'), sp),
+                initializer_list(f_field_initializer),
+                pair(sp, char('}')),
+            ),
+            FInitializerExpression::Compound,
+        ),
+        map(
+            delimited(
+                pair(char('['), sp),
+                initializer_list(f_element_initializer),
+                pair(sp, char(']')),
+            ),
+            FInitializerExpression::Bracket,
+        ),
+        map(f_expression, FInitializerExpression::Expression),
+    ))(s)
+}
+
+/// Comma separated, possibly empty list allowing a trailing comma.
+fn initializer_list<'a, T>(
+    element: fn(Input<'a>) -> IResult<Input<'a>, T>,
+) -> impl FnMut(Input<'a>) -> IResult<Input<'a>, Vec<T>> {
+    map(
+        opt(terminated(
+            separated_list1(tuple((sp, char(','), sp)), element),
+            opt(pair(sp, char(','))),
+        )),
+        Option::unwrap_or_default,
+    )
+}
+
+fn f_field_initializer(s: Input) -> IResult<Input, FFieldInitializer> {
+    let (s, element) = identifier(s)?;
+    let (s, _) = tuple((sp, char(':'), sp))(s)?;
+    let (s, value) = f_initializer_expression(s)?;
+    Ok((
+        s,
+        FFieldInitializer {
+            element: element.to_string(),
+            value,
+        },
+    ))
+}
+
+fn f_element_initializer(s: Input) -> IResult<Input, FElementInitializer> {
+    let (s, first) = f_initializer_expression(s)?;
+    let (s, second) = opt(preceded(
+        tuple((sp, tag("=>"), sp)),
+        f_initializer_expression,
+    ))(s)?;
+    Ok((s, FElementInitializer { first, second }))
+}
+
+/// Implicit array suffix of a type reference, e.g. the `[]` in `UInt8[] data`.
+pub fn implicit_array(s: Input) -> IResult<Input, bool> {
+    value(true, tuple((sp, char('['), sp, char(']'))))(s)
+}
+
+/// Annotation block, e.g. `<** @description: Current speed **>`
+pub fn f_annotation_block(s: Input) -> IResult<Input, FAnnotationBlock> {
+    let start = s;
+    let (s, _) = tag("<**")(s)?;
+    let (s, elements) = many1(f_annotation)(s)?;
+    let (s, _) = multispace0(s)?;
+    let (s, _) = tag("**>")(s)?;
+    Ok((
+        s,
+        FAnnotationBlock {
+            elements,
+            span: span(start, s),
+        },
+    ))
+}
+
+pub fn f_annotation(s: Input) -> IResult<Input, FAnnotation> {
+    let start = s;
+    let (s, _) = multispace0(s)?;
+    let (s, _) = char('@')(s)?;
+    let (s, r#type) = f_annotation_type(s)?;
+    let (s, _) = space0(s)?;
+    let (s, _) = char(':')(s)?;
+    let (s, comment) = f_annotation_text(s)?;
+    Ok((
+        s,
+        FAnnotation {
+            r#type: Some(r#type),
+            comment: Some(comment.trim().to_string()).filter(|c| !c.is_empty()),
+            span: span(start, s),
+        },
+    ))
+}
 
-    #[test]
-    pub fn f_method_test() {
-        let def = "method sayHello {
-        in {
-            String name
+/// Annotation text up to the next `@tag:` starting a line or the closing `**>`,
+/// so `*` and `@` may appear within the text, e.g. `@author: jane@example.com`.
+/// A backslash escapes the following character.
+fn f_annotation_text(s: Input) -> IResult<Input, String> {
+    let fragment = *s.fragment();
+    let mut text = String::new();
+    let mut end = fragment.len();
+    let mut chars = fragment.char_indices();
+    while let Some((i, c)) = chars.next() {
+        let rest = &fragment[i..];
+        if rest.starts_with("**>") || (c == '\n' && starts_annotation(&rest[1..])) {
+            end = i;
+            break;
         }
-        out {
-            String value_a
-            Double value_b
-            UInt32 value_c
+        match c {
+            '\\' => text.extend(chars.next().map(|(_, escaped)| escaped)),
+            c => text.push(c),
         }
     }
+    let (s, _) = s.take_split(end);
+    Ok((s, text))
 }
-";
 
-        let in_args = vec![FArgument {
-            name: "name".to_string(),
-            comment: None,
-            r#type: FTypeRef {
-                predefined: Some(FBasicTypeId::String),
-            },
-            array: None,
-        }];
-
-        let out_args = vec![
-            FArgument {
-                name: "value_a".to_string(),
-                comment: None,
-                r#type: FTypeRef {
-                    predefined: Some(FBasicTypeId::String),
-                },
-                array: None,
-            },
-            FArgument {
-                name: "value_b".to_string(),
-                comment: None,
-                r#type: FTypeRef {
-                    predefined: Some(FBasicTypeId::Double),
-                },
-                array: None,
-            },
-            FArgument {
-                name: "value_c".to_string(),
-                comment: None,
-                r#type: FTypeRef {
-                    predefined: Some(FBasicTypeId::UInt32),
-                },
-                array: None,
-            },
-        ];
+/// Whether `s` starts with an annotation tag like `@author:`, possibly indented.
+fn starts_annotation(s: &str) -> bool {
+    let tag = match s.trim_start().strip_prefix('@') {
+        Some(tag) => tag,
+        None => return false,
+    };
+    let rest = tag.trim_start_matches(is_annotation_tag_char);
+    rest.len() < tag.len() && rest.trim_start_matches([' ', '\t']).starts_with(':')
+}
 
-        let exp_method = FMethod {
-            name: "sayHello".to_string(),
-            comment: None,
-            fire_and_forget: None,
-            in_args,
+fn is_annotation_tag_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Annotation tag, unknown tags are kept as [`FAnnotationType::Custom`].
+pub fn f_annotation_type(s: Input) -> IResult<Input, FAnnotationType> {
+    map(take_while1(is_annotation_tag_char), |tag: Input| {
+        FAnnotationType::from_tag(&tag)
+    })(s)
+}
+
+pub fn f_broadcast(s: Input) -> IResult<Input, FBroadcast> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, comment) = opt(f_annotation_block)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("broadcast")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = identifier(s)?;
+    let (s, selector) = opt(selector)(s)?;
+    let (s, selective) = opt(preceded(sp, value(true, keyword("selective"))))(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, out_args) = element("out args", f_method_out_args)(s)?;
+    let (s, _) = brc_close(s)?;
+    Ok((
+        s,
+        FBroadcast {
+            name: name.to_string(),
+            selector: selector.map(|s| s.to_string()),
+            comment,
             out_args,
-            error_enum: None,
-            errors: None,
-        };
+            selective,
+            span: span(start, s),
+        },
+    ))
+}
+
+pub fn e_string(s: Input) -> IResult<Input, Input> {
+    let (s, e_str) = take_till(|c: char| !c.is_alphanum() && c != '_' && c != '-' && c != '.')(s)?;
+    Ok((s, e_str))
+}
 
-        let (_, method) = f_method(def).unwrap();
-        assert_eq!(method, exp_method);
+/// Span from `start` up to `end`, the input remaining after an element.
+pub(crate) fn span(start: Input, end: Input) -> Span {
+    Span {
+        start: start.location_offset(),
+        end: end.location_offset(),
+        line: start.location_line(),
+        column: start.get_utf8_column(),
+        end_line: end.location_line(),
+        end_column: end.get_utf8_column(),
     }
+}
 
-    #[test]
-    pub fn f_version_test() {
-        let def = "version { major 1 minor 0 }\n";
+/// `nom`'s `tag` recording `token` as expected on failure.
+pub fn tag<'a>(token: &'static str) -> impl FnMut(Input<'a>) -> IResult<Input<'a>, Input<'a>> {
+    move |s: Input<'a>| {
+        nom::bytes::complete::tag(token)(s).map_err(|e: nom::Err<Error<Input>>| {
+            e.map(|_| Error::expected(s, Expected::Token(token)))
+        })
+    }
+}
+
+/// Commits to `parser` once `keyword`, possibly preceded by an annotation block, is found.
+/// Errors after that point are failures so alternatives and repetitions do not backtrack.
+pub fn committed<'a, O>(
+    keyword: &'static str,
+    parser: impl FnMut(Input<'a>) -> IResult<Input<'a>, O>,
+) -> impl FnMut(Input<'a>) -> IResult<Input<'a>, O> {
+    preceded(
+        peek(tuple((
+            sp,
+            opt(f_annotation_block),
+            sp,
+            self::keyword(keyword),
+        ))),
+        cut(parser),
+    )
+}
 
-        let exp_version = FVersion { major: 1, minor: 0 };
+/// Element introduced by the first word of `label`, e.g. `method` or `in args`,
+/// recorded as context of errors within it.
+pub fn element<'a, O>(
+    label: &'static str,
+    parser: impl FnMut(Input<'a>) -> IResult<Input<'a>, O>,
+) -> impl FnMut(Input<'a>) -> IResult<Input<'a>, O> {
+    let keyword = label.split(' ').next().unwrap_or(label);
+    committed(keyword, context(label, parser))
+}
 
-        let (_, version) = f_version(def).unwrap();
-        assert_eq!(version, exp_version);
+/// Repeats `item` until `close` succeeds. If neither matches, the expected tokens of both
+/// are reported, or the error of `item` if it got further into the input.
+pub fn items_until<'a, O, C>(
+    mut item: impl FnMut(Input<'a>) -> IResult<Input<'a>, O>,
+    mut close: impl FnMut(Input<'a>) -> IResult<Input<'a>, C>,
+) -> impl FnMut(Input<'a>) -> IResult<Input<'a>, (Vec<O>, C)> {
+    move |mut s: Input<'a>| {
+        let mut items = Vec::new();
+        loop {
+            let close_error = match close(s) {
+                Ok((s, close)) => return Ok((s, (items, close))),
+                Err(nom::Err::Error(e)) => e,
+                Err(e) => return Err(e),
+            };
+            match item(s) {
+                Ok((rest, _)) if rest.len() == s.len() => {
+                    return Err(nom::Err::Error(close_error));
+                }
+                Ok((rest, o)) => {
+                    items.push(o);
+                    s = rest;
+                }
+                Err(nom::Err::Error(e)) => return Err(nom::Err::Error(close_error.or(e))),
+                Err(e) => return Err(e),
+            }
+        }
     }
+}
 
-    #[test]
-    pub fn f_broadcast_test() {
-        let def = "broadcast Greeting {
-    out {
-        String message
+/// Reports failures of `parser` at its very start as expecting `name`.
+pub fn expect<'a, O>(
+    name: &'static str,
+    mut parser: impl FnMut(Input<'a>) -> IResult<Input<'a>, O>,
+) -> impl FnMut(Input<'a>) -> IResult<Input<'a>, O> {
+    move |s: Input<'a>| {
+        parser(s).map_err(|e| {
+            e.map(|e| {
+                if e.input.len() == s.len() {
+                    Error::expected(s, Expected::Named(name))
+                } else {
+                    e
+                }
+            })
+        })
     }
 }
-";
-        let out_args = vec![FArgument {
-            name: "message".to_string(),
-            comment: None,
-            r#type: FTypeRef {
-                predefined: Some(FBasicTypeId::String),
-            },
-            array: None,
-        }];
 
-        let exp_broadcast = FBroadcast {
-            name: "Greeting".to_string(),
-            comment: None,
-            out_args,
-            selective: None,
-        };
+/// Whitespace including line (`// ...`) and block (`/* ... */`) comments.
+///
+/// Parsers skip it before each of their tokens and never require it, words are
+/// kept apart by [`keyword`] and [`identifier`] consuming all identifier characters.
+/// Any layout, e.g. CRLF line endings or `method foo{`, therefore parses alike.
+pub fn sp(s: Input) -> IResult<Input, ()> {
+    value((), many0(alt((multispace1, line_comment, block_comment))))(s)
+}
 
-        let (_, broadcast) = f_broadcast(def).unwrap();
-        assert_eq!(broadcast, exp_broadcast);
-    }
+pub fn line_comment(s: Input) -> IResult<Input, Input> {
+    recognize(pair(tag("//"), not_line_ending))(s)
+}
 
-    #[test]
-    pub fn import_uri_test() {
-        let (_, ns) = imported_namespace(" org.franca*.examples.demo.* from").unwrap();
-        assert_eq!(ns, "org.franca*.examples.demo.*");
-        let def = r##"import org.franca.examples.demo.* from "basic.fidl""##;
+pub fn block_comment(s: Input) -> IResult<Input, Input> {
+    recognize(tuple((tag("/*"), take_until("*/"), tag("*/"))))(s)
+}
 
-        let exp_import = Import {
-            imported_namespace: Some("org.franca.examples.demo.*".to_string()),
-            import_uri: Some("basic.fidl".to_string()),
-        };
+/// Franca `ID`, a letter or underscore followed by letters, digits or underscores.
+pub fn identifier(s: Input) -> IResult<Input, Input> {
+    expect(
+        "identifier",
+        recognize(pair(
+            satisfy(|c: char| c.is_alpha() || c == '_'),
+            take_while(|c: char| c.is_alphanum() || c == '_'),
+        )),
+    )(s)
+}
 
-        let (_, broadcast) = import(def).unwrap();
-        assert_eq!(broadcast, exp_import);
-    }
+/// Fully qualified name, e.g. `org.example.MyType`.
+pub fn fqn(s: Input) -> IResult<Input, Input> {
+    recognize(separated_list1(char('.'), identifier))(s)
+}
 
-    #[test]
-    pub fn import_uri_test_without_from() {
-        let def = r##"import "platform:/plugin/abc.fdepl"""##;
+/// Inheritance clause, e.g. ` extends org.example.Base`.
+pub fn extends(s: Input) -> IResult<Input, Input> {
+    preceded(tuple((sp, keyword("extends"), sp)), fqn)(s)
+}
 
-        let exp_import = Import {
-            imported_namespace: None,
-            import_uri: Some("platform:/plugin/abc.fdepl".to_string()),
-        };
+/// Keyword that is not directly followed by further identifier characters.
+pub fn keyword<'a>(
+    keyword: &'static str,
+) -> impl FnMut(Input<'a>) -> IResult<Input<'a>, Input<'a>> {
+    terminated(
+        tag(keyword),
+        not(satisfy(|c: char| c.is_alphanum() || c == '_')),
+    )
+}
 
-        let (_, broadcast) = import(def).unwrap();
-        assert_eq!(broadcast, exp_import);
-    }
+/// Selector suffix of an overloaded method or broadcast name, e.g. `:v2`.
+pub fn selector(s: Input) -> IResult<Input, Input> {
+    preceded(char(':'), identifier)(s)
 }
 
-pub fn f_model(s: &str) -> IResult<&str, FModel> {
-    let (s, _) = multispace0(s)?;
-    let (s, _) = tag("package")(s)?;
-    let (s, _) = space1(s)?;
-    let (s, name) = e_string(s)?;
+/// `{` after any whitespace and comments.
+pub fn brc_open(s: Input) -> IResult<Input, char> {
+    preceded(sp, char('{'))(s)
+}
+
+/// `}` after any whitespace and comments.
+pub fn brc_close(s: Input) -> IResult<Input, char> {
+    preceded(sp, char('}'))(s)
+}
 
-    let (s, imports) = many0(import)(s)?;
-    let (s, interfaces) = many0(f_interface)(s)?;
+pub fn f_argument(s: Input) -> IResult<Input, FArgument> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, comment) = opt(f_annotation_block)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, r#type) = f_type_ref(s)?;
+    let (s, array) = opt(implicit_array)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = identifier(s)?;
     Ok((
         s,
-        FModel {
+        FArgument {
             name: name.to_string(),
-            imports,
-            interfaces,
-            type_collections: Vec::new(),
+            comment,
+            r#type,
+            array: array.is_some(),
+            span: span(start, s),
         },
     ))
 }
 
-pub fn f_interface(s: &str) -> IResult<&str, FInterface> {
-    let (s, _) = multispace0(s)?;
-    let (s, _) = tag("interface")(s)?;
-    let (s, _) = space1(s)?;
-    let (s, name) = e_string(s)?;
-    let (s, _) = multispace1(s)?;
+pub fn f_method_in_args(s: Input) -> IResult<Input, Vec<FArgument>> {
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("in")(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, (in_args, _)) = items_until(f_argument, brc_close)(s)?;
+    Ok((s, in_args))
+}
+
+pub fn f_method_out_args(s: Input) -> IResult<Input, Vec<FArgument>> {
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("out")(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, (in_args, _)) = items_until(f_argument, brc_close)(s)?;
+    Ok((s, in_args))
+}
 
+pub fn f_method(s: Input) -> IResult<Input, FMethod> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, comment) = opt(f_annotation_block)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("method")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = identifier(s)?;
+    let (s, selector) = opt(selector)(s)?;
+    let (s, fire_and_forget) = opt(preceded(sp, value(true, keyword("fireAndForget"))))(s)?;
     let (s, _) = brc_open(s)?;
-    let (s, version) = opt(f_version)(s)?;
-    let (s, methods) = many0(f_method)(s)?;
-    let (s, broadcasts) = many0(f_broadcast)(s)?;
+    let (s, in_args) = opt(element("in args", f_method_in_args))(s)?;
+    // fireAndForget methods are never replied to, so `out` and `error` blocks are syntax errors
+    let (s, out_args, error) = match fire_and_forget {
+        Some(true) => (s, None, None),
+        _ => {
+            let (s, out_args) = opt(element("out args", f_method_out_args))(s)?;
+            let (s, error) = opt(element("error", f_method_error))(s)?;
+            (s, out_args, error)
+        }
+    };
     let (s, _) = brc_close(s)?;
 
+    let (error_enum, errors) = match error {
+        Some(FMethodError::Reference(error_enum)) => (Some(error_enum), None),
+        Some(FMethodError::Inline(errors)) => (None, Some(errors)),
+        None => (None, None),
+    };
+
     Ok((
         s,
-        FInterface {
+        FMethod {
             name: name.to_string(),
-            comment: None,
-            version,
-            attributes: Vec::new(),
-            methods,
-            broadcasts,
+            selector: selector.map(|s| s.to_string()),
+            comment,
+            fire_and_forget,
+            in_args: in_args.unwrap_or_default(),
+            out_args: out_args.unwrap_or_default(),
+            error_enum,
+            errors,
+            span: span(start, s),
         },
     ))
 }
 
-pub fn f_broadcast(s: &str) -> IResult<&str, FBroadcast> {
-    let (s, _) = multispace0(s)?;
-    let (s, _) = tag("broadcast")(s)?;
-    let (s, _) = space1(s)?;
-    let (s, name) = e_string(s)?;
-    let (s, _) = multispace1(s)?;
-    let (s, _) = brc_open(s)?;
-    let (s, out_args) = f_method_out_args(s)?;
-    let (s, _) = multispace1(s)?;
-    let (s, _) = brc_close(s)?;
+/// Error specification of a method, either a reference to an enumeration
+/// or an inline enumeration.
+enum FMethodError {
+    Reference(String),
+    Inline(FEnumerationType),
+}
+
+/// e.g. `error SomeEnum`, `error { A B C }` or `error extends Base { D }`
+fn f_method_error(s: Input) -> IResult<Input, FMethodError> {
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("error")(s)?;
+    alt((
+        map(f_method_error_inline, FMethodError::Inline),
+        map(preceded(sp, fqn), |error_enum| {
+            FMethodError::Reference(error_enum.to_string())
+        }),
+    ))(s)
+}
+
+fn f_method_error_inline(s: Input) -> IResult<Input, FEnumerationType> {
+    let start = s;
+    let (s, base) = opt(extends)(s)?;
+    let (s, enumerators) = f_enumerators(s)?;
     Ok((
         s,
-        FBroadcast {
-            name: name.to_string(),
+        FEnumerationType {
+            name: String::new(),
             comment: None,
-            out_args,
-            selective: None,
+            enumerators,
+            base: base.map(|b| b.to_string()),
+            span: span(start, s),
         },
     ))
 }
 
-pub fn e_string(s: &str) -> IResult<&str, &str> {
-    let (s, e_str) = take_till(|c: char| !c.is_alphanum() && c != '_' && c != '-' && c != '.')(s)?;
-    Ok((s, e_str))
-}
-
-pub fn brc_open(s: &str) -> IResult<&str, char> {
-    char('{')(s)
+/// e.g. `contract { vars { UInt32 count } PSM { initial idle state idle { on call start -> idle } } }`
+pub fn f_contract(s: Input) -> IResult<Input, FContract> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("contract")(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, variables) = opt(f_contract_variables)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("PSM")(s)?;
+    let (s, state_graph) = f_state_graph(s)?;
+    let (s, _) = brc_close(s)?;
+    Ok((
+        s,
+        FContract {
+            variables: variables.unwrap_or_default(),
+            state_graph,
+            span: span(start, s),
+        },
+    ))
 }
 
-pub fn brc_close(s: &str) -> IResult<&str, char> {
-    char('}')(s)
+fn f_contract_variables(s: Input) -> IResult<Input, Vec<FDeclaration>> {
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("vars")(s)?;
+    let (s, _) = brc_open(s)?;
+    let (s, variables) = many0(f_declaration)(s)?;
+    let (s, _) = brc_close(s)?;
+    Ok((s, variables))
 }
 
-pub fn f_argument(s: &str) -> IResult<&str, FArgument> {
-    let (s, _) = multispace0(s)?;
+/// e.g. `UInt32 count = 0`
+pub fn f_declaration(s: Input) -> IResult<Input, FDeclaration> {
+    let (s, _) = sp(s)?;
+    let start = s;
     let (s, r#type) = f_type_ref(s)?;
-    let (s, _) = space1(s)?;
-    let (s, name) = e_string(s)?;
-    let (s, _) = space0(s)?;
-    let (s, _) = line_ending(s)?;
+    let (s, array) = opt(implicit_array)(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = identifier(s)?;
+    let (s, rhs) = opt(preceded(
+        tuple((sp, char('='), sp)),
+        f_initializer_expression,
+    ))(s)?;
     Ok((
         s,
-        FArgument {
+        FDeclaration {
             name: name.to_string(),
-            comment: None,
             r#type,
-            array: None,
+            array: array.is_some(),
+            rhs,
+            span: span(start, s),
         },
     ))
 }
 
-pub fn f_method_in_args(s: &str) -> IResult<&str, Vec<FArgument>> {
-    let (s, _) = multispace0(s)?;
-    let (s, _) = tag("in")(s)?;
-    let (s, _) = multispace1(s)?;
+pub fn f_state_graph(s: Input) -> IResult<Input, FStateGraph> {
+    let (s, _) = sp(s)?;
+    let start = s;
     let (s, _) = brc_open(s)?;
-    let (s, in_args) = many0(f_argument)(s)?;
-    let (s, _) = multispace1(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("initial")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, initial) = identifier(s)?;
+    let (s, states) = many0(element("state", f_state))(s)?;
     let (s, _) = brc_close(s)?;
-    Ok((s, in_args))
+    Ok((
+        s,
+        FStateGraph {
+            initial: initial.to_string(),
+            states,
+            span: span(start, s),
+        },
+    ))
 }
 
-pub fn f_method_out_args(s: &str) -> IResult<&str, Vec<FArgument>> {
-    let (s, _) = multispace0(s)?;
-    let (s, _) = tag("out")(s)?;
-    let (s, _) = multispace1(s)?;
+pub fn f_state(s: Input) -> IResult<Input, FState> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("state")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, name) = identifier(s)?;
     let (s, _) = brc_open(s)?;
-    let (s, in_args) = many0(f_argument)(s)?;
-    let (s, _) = multispace1(s)?;
+    let (s, transitions) = many0(committed("on", context("transition", f_transition)))(s)?;
     let (s, _) = brc_close(s)?;
-    Ok((s, in_args))
+    Ok((
+        s,
+        FState {
+            name: name.to_string(),
+            transitions,
+            span: span(start, s),
+        },
+    ))
 }
 
-pub fn f_method(s: &str) -> IResult<&str, FMethod> {
-    let (s, _) = multispace0(s)?;
-    let (s, _) = tag("method")(s)?;
-    let (s, _) = space1(s)?;
-    let (s, name) = e_string(s)?;
-    let (s, _) = multispace1(s)?;
+/// e.g. `on call foo [count < 3] -> busy { count += 1 }`
+pub fn f_transition(s: Input) -> IResult<Input, FTransition> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("on")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, trigger) = f_trigger(s)?;
+    let (s, guard) = opt(preceded(sp, f_guard))(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = tag("->")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, to) = identifier(s)?;
+    let (s, action) = opt(preceded(sp, f_block_expression))(s)?;
+    Ok((
+        s,
+        FTransition {
+            trigger,
+            guard,
+            to: to.to_string(),
+            action,
+            span: span(start, s),
+        },
+    ))
+}
+
+pub fn f_trigger(s: Input) -> IResult<Input, FTrigger> {
+    let (s, event) = alt((
+        map(event_target("call"), FEventOnIf::Call),
+        map(event_target("respond"), FEventOnIf::Respond),
+        map(event_target("signal"), FEventOnIf::Signal),
+        map(event_target("error"), FEventOnIf::Error),
+        map(event_target("set"), FEventOnIf::Set),
+        map(event_target("update"), FEventOnIf::Update),
+    ))(s)?;
+    Ok((s, FTrigger { event }))
+}
+
+/// Event keyword followed by the name of the method, broadcast or attribute.
+fn event_target<'a>(event: &'static str) -> impl FnMut(Input<'a>) -> IResult<Input<'a>, String> {
+    map(
+        preceded(
+            pair(keyword(event), sp),
+            recognize(pair(fqn, opt(selector))),
+        ),
+        |target: Input| target.to_string(),
+    )
+}
+
+pub fn f_guard(s: Input) -> IResult<Input, FGuard> {
+    let (s, _) = char('[')(s)?;
+    let (s, _) = sp(s)?;
+    let (s, condition) = f_expression(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = char(']')(s)?;
+    Ok((s, FGuard { condition }))
+}
+
+pub fn f_block_expression(s: Input) -> IResult<Input, FBlockExpression> {
     let (s, _) = brc_open(s)?;
-    let (s, in_args) = f_method_in_args(s)?;
-    let (s, out_args) = f_method_out_args(s)?;
-    let (s, _) = multispace0(s)?;
+    let (s, statements) = many0(preceded(sp, f_statement))(s)?;
     let (s, _) = brc_close(s)?;
-    let (s, _) = space0(s)?;
-    let (s, _) = line_ending(s)?;
+    Ok((s, FBlockExpression { statements }))
+}
+
+pub fn f_statement(s: Input) -> IResult<Input, FStatement> {
+    alt((
+        f_if_statement,
+        map(f_block_expression, FStatement::Block),
+        f_assignment,
+    ))(s)
+}
+
+/// e.g. `if (count > 3) state = 1 else { state = 2 }`
+fn f_if_statement(s: Input) -> IResult<Input, FStatement> {
+    let (s, _) = keyword("if")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = char('(')(s)?;
+    let (s, _) = sp(s)?;
+    let (s, condition) = f_expression(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = char(')')(s)?;
+    let (s, _) = sp(s)?;
+    let (s, then) = f_statement(s)?;
+    let (s, r#else) = opt(preceded(tuple((sp, keyword("else"), sp)), f_statement))(s)?;
     Ok((
         s,
-        FMethod {
-            name: name.to_string(),
-            comment: None,
-            fire_and_forget: None,
-            in_args,
-            out_args,
-            error_enum: None,
-            errors: None,
+        FStatement::If {
+            condition,
+            then: Box::new(then),
+            r#else: r#else.map(Box::new),
         },
     ))
 }
 
-pub fn f_version(s: &str) -> IResult<&str, FVersion> {
-    let (s, _) = multispace0(s)?;
-    let (s, _) = tag("version")(s)?;
-    let (s, _) = multispace1(s)?;
+/// e.g. `count += 1`
+fn f_assignment(s: Input) -> IResult<Input, FStatement> {
+    let (s, lhs) = f_qualified_element_ref(s)?;
+    let (s, _) = sp(s)?;
+    let (s, op) = f_assignment_op(s)?;
+    let (s, _) = sp(s)?;
+    let (s, rhs) = f_expression(s)?;
+    Ok((s, FStatement::Assignment { lhs, op, rhs }))
+}
+
+pub fn f_assignment_op(s: Input) -> IResult<Input, FAssignmentOp> {
+    alt((
+        value(FAssignmentOp::ShiftLeftAssign, tag("<<=")),
+        value(FAssignmentOp::ShiftRightAssign, tag(">>=")),
+        value(FAssignmentOp::MulAssign, tag("*=")),
+        value(FAssignmentOp::DivAssign, tag("/=")),
+        value(FAssignmentOp::ModAssign, tag("%=")),
+        value(FAssignmentOp::AddAssign, tag("+=")),
+        value(FAssignmentOp::SubAssign, tag("-=")),
+        value(FAssignmentOp::AndAssign, tag("&=")),
+        value(FAssignmentOp::XorAssign, tag("^=")),
+        value(FAssignmentOp::OrAssign, tag("|=")),
+        value(FAssignmentOp::Assign, terminated(tag("="), not(char('=')))),
+    ))(s)
+}
+
+/// Expression with the usual precedence, from weakest to strongest binding:
+/// `||`, `&&`, `==` `!=`, `<` `<=` `>` `>=`, `+` `-`, `*` `/`, unary `!` `-`.
+pub fn f_expression(s: Input) -> IResult<Input, FExpression> {
+    f_logical_or_expression(s)
+}
+
+fn f_logical_or_expression(s: Input) -> IResult<Input, FExpression> {
+    binary_expression(s, f_logical_and_expression, |s| {
+        value(FOperator::Or, tag("||"))(s)
+    })
+}
+
+fn f_logical_and_expression(s: Input) -> IResult<Input, FExpression> {
+    binary_expression(s, f_equality_expression, |s| {
+        value(FOperator::And, tag("&&"))(s)
+    })
+}
+
+fn f_equality_expression(s: Input) -> IResult<Input, FExpression> {
+    binary_expression(s, f_relational_expression, |s| {
+        alt((
+            value(FOperator::Equal, tag("==")),
+            value(FOperator::Unequal, tag("!=")),
+        ))(s)
+    })
+}
+
+fn f_relational_expression(s: Input) -> IResult<Input, FExpression> {
+    binary_expression(s, f_additive_expression, |s| {
+        alt((
+            value(FOperator::SmallerOrEqual, tag("<=")),
+            value(FOperator::GreaterOrEqual, tag(">=")),
+            value(FOperator::Smaller, char('<')),
+            value(FOperator::Greater, char('>')),
+        ))(s)
+    })
+}
+
+fn f_additive_expression(s: Input) -> IResult<Input, FExpression> {
+    binary_expression(s, f_multiplicative_expression, |s| {
+        alt((
+            value(FOperator::Addition, terminated(char('+'), not(char('=')))),
+            value(
+                FOperator::Subtraction,
+                terminated(char('-'), not(one_of(">="))),
+            ),
+        ))(s)
+    })
+}
+
+fn f_multiplicative_expression(s: Input) -> IResult<Input, FExpression> {
+    binary_expression(s, f_unary_expression, |s| {
+        alt((
+            value(
+                FOperator::Multiplication,
+                terminated(char('*'), not(char('='))),
+            ),
+            value(FOperator::Division, terminated(char('/'), not(char('=')))),
+        ))(s)
+    })
+}
+
+/// Left associative chain of `operand`s separated by `operator`s.
+fn binary_expression(
+    s: Input,
+    operand: fn(Input) -> IResult<Input, FExpression>,
+    operator: fn(Input) -> IResult<Input, FOperator>,
+) -> IResult<Input, FExpression> {
+    let (mut s, mut left) = operand(s)?;
+    loop {
+        match pair(preceded(sp, operator), preceded(sp, operand))(s) {
+            Ok((rest, (op, right))) => {
+                left = FExpression::Binary {
+                    left: Box::new(left),
+                    op,
+                    right: Box::new(right),
+                };
+                s = rest;
+            }
+            Err(nom::Err::Error(_)) => return Ok((s, left)),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn f_unary_expression(s: Input) -> IResult<Input, FExpression> {
+    let unary = map(
+        pair(
+            alt((
+                value(FOperator::Not, char('!')),
+                value(FOperator::Subtraction, char('-')),
+            )),
+            preceded(sp, f_unary_expression),
+        ),
+        |(op, operand)| FExpression::Unary {
+            op,
+            operand: Box::new(operand),
+        },
+    );
+    alt((unary, f_primary_expression))(s)
+}
+
+fn f_primary_expression(s: Input) -> IResult<Input, FExpression> {
+    alt((
+        delimited(pair(char('('), sp), f_expression, pair(sp, char(')'))),
+        map(f_constant, FExpression::Constant),
+        map(f_qualified_element_ref, FExpression::ElementRef),
+    ))(s)
+}
+
+pub fn f_constant(s: Input) -> IResult<Input, FConstant> {
+    alt((
+        value(FConstant::Boolean(true), keyword("true")),
+        value(FConstant::Boolean(false), keyword("false")),
+        map(terminated(decimal_number, char('f')), |f: Input| {
+            FConstant::Float(f.to_string())
+        }),
+        map(terminated(decimal_number, char('d')), |d: Input| {
+            FConstant::Double(d.to_string())
+        }),
+        map(integer, FConstant::Integer),
+        map(string_literal, FConstant::String),
+    ))(s)
+}
+
+/// Unsigned decimal, hexadecimal (`0x1F`) or binary (`0b0101`) integer literal,
+/// signs are unary expressions.
+pub fn integer(s: Input) -> IResult<Input, i128> {
+    let integer = alt((
+        map_res(
+            preceded(alt((tag("0x"), tag("0X"))), hex_digit1),
+            |digits: Input| i128::from_str_radix(&digits, 16),
+        ),
+        map_res(
+            preceded(
+                alt((tag("0b"), tag("0B"))),
+                take_while1(|c| c == '0' || c == '1'),
+            ),
+            |digits: Input| i128::from_str_radix(&digits, 2),
+        ),
+        map_res(digit1, |digits: Input| digits.parse::<i128>()),
+    ));
+    expect("integer", integer)(s)
+}
+
+/// Integer literal with an optional sign, e.g. the bounds of `Integer(-0x10, 0x10)`.
+fn signed_integer(s: Input) -> IResult<Input, i128> {
+    let (s, sign) = opt(one_of("+-"))(s)?;
+    let (s, integer) = integer(s)?;
+    match sign {
+        Some('-') => Ok((s, -integer)),
+        _ => Ok((s, integer)),
+    }
+}
+
+/// e.g. `3.14` or `1.5e-3`
+fn decimal_number(s: Input) -> IResult<Input, Input> {
+    recognize(tuple((
+        digit1,
+        char('.'),
+        digit1,
+        opt(tuple((one_of("eE"), opt(one_of("+-")), digit1))),
+    )))(s)
+}
+
+/// Double quoted string supporting backslash escapes.
+pub fn string_literal(s: Input) -> IResult<Input, String> {
+    let (s, _) = char('"')(s)?;
+    let (s, string) = opt(escaped_transform(is_not("\\\""), '\\', anychar))(s)?;
+    let (s, _) = char('"')(s)?;
+    Ok((s, string.unwrap_or_default()))
+}
+
+/// e.g. `position->latitude`
+pub fn f_qualified_element_ref(s: Input) -> IResult<Input, FQualifiedElementRef> {
+    let (s, element) = fqn(s)?;
+    let (s, fields) = many0(preceded(tuple((sp, tag("->"), sp)), identifier))(s)?;
+    Ok((
+        s,
+        FQualifiedElementRef {
+            element: element.to_string(),
+            fields: fields.into_iter().map(|f| f.to_string()).collect(),
+        },
+    ))
+}
+
+pub fn f_version(s: Input) -> IResult<Input, FVersion> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("version")(s)?;
     let (s, _) = brc_open(s)?;
 
-    let (s, _) = multispace1(s)?;
-    let (s, _) = tag("major")(s)?;
-    let (s, _) = multispace1(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("major")(s)?;
+    let (s, _) = sp(s)?;
     let (s, major) = complete::u16(s)?;
 
-    let (s, _) = multispace1(s)?;
-    let (s, _) = tag("minor")(s)?;
-    let (s, _) = multispace1(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("minor")(s)?;
+    let (s, _) = sp(s)?;
     let (s, minor) = complete::u16(s)?;
 
-    let (s, _) = multispace1(s)?;
     let (s, _) = brc_close(s)?;
-    let (s, _) = line_ending(s)?;
 
-    Ok((s, FVersion { major, minor }))
+    Ok((
+        s,
+        FVersion {
+            major,
+            minor,
+            span: span(start, s),
+        },
+    ))
 }
 
-fn imported_namespace(s: &str) -> IResult<&str, &str> {
-    let (s, _) = space1(s)?;
+fn imported_namespace(s: Input) -> IResult<Input, Input> {
+    let (s, _) = sp(s)?;
     let (s, imported_namespace) = take_till1(|c: char| {
-        !(c.is_alphanum() || c == '_' || c == '-' || c == '\u{002A}' || c == '*' || c == '.')
+        !(c.is_alphanum() || c == '_' || c == '-' || c == '\u{002A}' || c == '.')
     })(s)?;
 
-    let (s, _) = space1(s)?;
-    let (s, _) = tag("from")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = keyword("from")(s)?;
     Ok((s, imported_namespace))
 }
 
-fn import_uri(s: &str) -> IResult<&str, &str> {
+fn import_uri(s: Input) -> IResult<Input, Input> {
     let (s, _) = char('"')(s)?;
     let (s, import_uri) = take_till(|c: char| c == '"' || c == '\n')(s)?;
     let (s, _) = char('"')(s)?;
     Ok((s, import_uri))
 }
 
-fn import(s: &str) -> IResult<&str, Import> {
-    let (s, _) = multispace0(s)?;
-    let (s, _) = tag("import")(s)?;
+fn import(s: Input) -> IResult<Input, Import> {
+    let (s, _) = sp(s)?;
+    let start = s;
+    let (s, _) = keyword("import")(s)?;
 
     // TODO model import currently not supported since it's not documented very well
     let (s, imported_namespace) = opt(imported_namespace)(s)?;
-    let (s, _) = space1(s)?;
+    let (s, _) = sp(s)?;
 
     let (s, import_uri) = import_uri(s)?;
     Ok((
@@ -439,23 +2564,66 @@ fn import(s: &str) -> IResult<&str, Import> {
         Import {
             imported_namespace: imported_namespace.map(|x| x.to_string()),
             import_uri: Some(import_uri.to_string()),
+            span: span(start, s),
         },
     ))
 }
 
-pub fn f_type_ref(s: &str) -> IResult<&str, FTypeRef> {
-    let (s, predefined) = opt(f_basic_type_id)(s)?;
-    // let (s, derived) = opt(f_type)(s)?;
+pub fn f_type_ref(s: Input) -> IResult<Input, FTypeRef> {
+    let start = s;
+    if let Ok((s, interval)) = f_integer_interval(s) {
+        return Ok((
+            s,
+            FTypeRef {
+                predefined: Some(FBasicTypeId::Integer),
+                derived: None,
+                interval: Some(interval),
+                span: span(start, s),
+            },
+        ));
+    }
+
+    let (s, name) = fqn(s)?;
+    let type_ref = match all_consuming(f_basic_type_id)(name) {
+        Ok((_, predefined)) => FTypeRef {
+            predefined: Some(predefined),
+            derived: None,
+            interval: None,
+            span: span(start, s),
+        },
+        Err(_) => FTypeRef {
+            predefined: None,
+            derived: Some(name.to_string()),
+            interval: None,
+            span: span(start, s),
+        },
+    };
+    Ok((s, type_ref))
+}
+
+/// e.g. `Integer(0, 100)` or `Integer(minInt, 100)`
+pub fn f_integer_interval(s: Input) -> IResult<Input, FIntegerInterval> {
+    let (s, _) = tag("Integer")(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = char('(')(s)?;
+    let (s, _) = sp(s)?;
+    let (s, lower_bound) = alt((value(None, keyword("minInt")), map(signed_integer, Some)))(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = char(',')(s)?;
+    let (s, _) = sp(s)?;
+    let (s, upper_bound) = alt((value(None, keyword("maxInt")), map(signed_integer, Some)))(s)?;
+    let (s, _) = sp(s)?;
+    let (s, _) = char(')')(s)?;
     Ok((
         s,
-        FTypeRef {
-            predefined,
-            // derived,
+        FIntegerInterval {
+            lower_bound,
+            upper_bound,
         },
     ))
 }
 
-pub fn f_basic_type_id(s: &str) -> IResult<&str, FBasicTypeId> {
+pub fn f_basic_type_id(s: Input) -> IResult<Input, FBasicTypeId> {
     alt((
         value(FBasicTypeId::UInt8, tag("UInt8")),
         value(FBasicTypeId::Int8, tag("Int8")),
@@ -465,7 +2633,6 @@ pub fn f_basic_type_id(s: &str) -> IResult<&str, FBasicTypeId> {
         value(FBasicTypeId::Int32, tag("Int32")),
         value(FBasicTypeId::UInt64, tag("UInt64")),
         value(FBasicTypeId::Int64, tag("Int64")),
-        // TODO ranges
         value(FBasicTypeId::Integer, tag("Integer")),
         value(FBasicTypeId::Boolean, tag("Boolean")),
         value(FBasicTypeId::Float, tag("Float")),
@@ -0,0 +1,876 @@
+use std::fmt;
+
+use crate::{
+    FAttribute, FBroadcast, FConstantDef, FContainer, FEnumerationType, FEnumerator, FField,
+    FInterface, FMethod, FModel, FStructType, FType, FTypeRef, FTypedElement, FUnionType,
+};
+
+/// Name that does not resolve to exactly one model element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+    /// No element of that name is visible.
+    Unknown(String),
+    /// Several equally near elements of that name are visible,
+    /// e.g. types of two imported type collections.
+    Ambiguous(String),
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::Unknown(name) => write!(f, "unknown element `{}`", name),
+            ResolveError::Ambiguous(name) => write!(f, "ambiguous element `{}`", name),
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+impl FModel {
+    /// Looks up a user defined type by its possibly qualified name as referenced
+    /// from within `container`.
+    ///
+    /// Accepted forms are `Type`, `Container.Type` and `package.Container.Type`
+    /// where `Container` is the name of an interface or type collection.
+    /// Types of anonymous type collections may be qualified by the package name only.
+    ///
+    /// Unqualified names are looked up in `container` first, then in the base interfaces
+    /// of an interface container, nearest first, and only then in anonymous type collections
+    /// and the containers of imported namespaces.
+    /// Without a container unqualified names are looked up in the whole model.
+    pub fn find_type<'a>(
+        &'a self,
+        name: &str,
+        container: Option<FContainer<'a>>,
+    ) -> Result<&'a FType, ResolveError> {
+        self.lookup_type(name, container).map(|(_, r#type)| r#type)
+    }
+
+    /// Looks up a constant definition, qualified and scoped like types in [`FModel::find_type`].
+    pub fn find_constant<'a>(
+        &'a self,
+        name: &str,
+        container: Option<FContainer<'a>>,
+    ) -> Result<&'a FConstantDef, ResolveError> {
+        self.lookup_constant(name, container)
+            .map(|(_, constant)| constant)
+    }
+
+    /// All interfaces and type collections, interfaces first.
+    pub fn containers(&self) -> impl Iterator<Item = FContainer<'_>> {
+        let interfaces = self.interfaces.iter().map(FContainer::Interface);
+        let type_collections = self.type_collections.iter().map(FContainer::TypeCollection);
+        interfaces.chain(type_collections)
+    }
+
+    /// All user defined types of all interfaces and type collections.
+    pub fn types(&self) -> impl Iterator<Item = &FType> {
+        let interface_types = self.interfaces.iter().flat_map(|i| i.types.iter());
+        let collection_types = self.type_collections.iter().flat_map(|t| t.types.iter());
+        interface_types.chain(collection_types)
+    }
+
+    /// Resolves a derived type reference found within `container` to its definition.
+    /// Returns `Ok(None)` for predefined types.
+    pub fn resolve_type_ref<'a>(
+        &'a self,
+        type_ref: &FTypeRef,
+        container: FContainer<'a>,
+    ) -> Result<Option<&'a FType>, ResolveError> {
+        match type_ref.derived.as_deref() {
+            Some(name) => self.find_type(name, Some(container)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Element type of an array typed element declared within `container`,
+    /// `None` if the element is no array.
+    /// The element type comes with the container it is resolved from.
+    ///
+    /// Covers implicit arrays (`UInt8[] data`) as well as references to named
+    /// `array Data of UInt8` definitions, also when hidden behind typedefs.
+    pub fn element_type<'a>(
+        &'a self,
+        element: &'a impl FTypedElement,
+        container: FContainer<'a>,
+    ) -> Option<(&'a FTypeRef, FContainer<'a>)> {
+        self.array_element_type(element.type_ref(), element.is_implicit_array(), container)
+    }
+
+    /// Like [`FModel::element_type`] for type references without an element,
+    /// e.g. map keys and values.
+    pub fn array_element_type<'a>(
+        &'a self,
+        type_ref: &'a FTypeRef,
+        implicit_array: bool,
+        container: FContainer<'a>,
+    ) -> Option<(&'a FTypeRef, FContainer<'a>)> {
+        if implicit_array {
+            return Some((type_ref, container));
+        }
+
+        let mut type_ref = type_ref;
+        let mut container = container;
+        let mut visited = Vec::new();
+        loop {
+            let name = type_ref.derived.as_deref()?;
+            let (type_container, r#type) = self.lookup_type(name, Some(container)).ok()?;
+            container = type_container;
+            match r#type {
+                FType::Array(array) => return Some((&array.element_type, container)),
+                FType::TypeDef(type_def) => {
+                    if visited.iter().any(|t| std::ptr::eq(*t, type_def)) {
+                        return None;
+                    }
+                    visited.push(type_def);
+                    type_ref = &type_def.actual_type;
+                    if type_def.array {
+                        return Some((type_ref, container));
+                    }
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// Looks up an interface by its name, optionally qualified by the package name.
+    pub fn find_interface(&self, name: &str) -> Option<&FInterface> {
+        let (qualifier, interface_name) = match name.rsplit_once('.') {
+            Some((qualifier, interface_name)) => (Some(qualifier), interface_name),
+            None => (None, name),
+        };
+
+        if qualifier.is_some_and(|q| q != self.name) {
+            return None;
+        }
+
+        self.interfaces.iter().find(|i| i.name == interface_name)
+    }
+
+    /// Inheritance chain of an interface starting with the root base interface
+    /// and ending with the interface itself.
+    /// Unresolvable bases end the chain, cyclic inheritance is visited only once.
+    pub fn interface_hierarchy<'a>(&'a self, interface: &'a FInterface) -> Vec<&'a FInterface> {
+        hierarchy(interface, |i| {
+            i.base.as_deref().and_then(|base| self.find_interface(base))
+        })
+    }
+
+    /// Methods of an interface including all inherited ones, base interface methods first.
+    pub fn all_methods<'a>(&'a self, interface: &'a FInterface) -> Vec<&'a FMethod> {
+        self.interface_hierarchy(interface)
+            .into_iter()
+            .flat_map(|i| i.methods.iter())
+            .collect()
+    }
+
+    /// Attributes of an interface including all inherited ones, base interface attributes first.
+    pub fn all_attributes<'a>(&'a self, interface: &'a FInterface) -> Vec<&'a FAttribute> {
+        self.interface_hierarchy(interface)
+            .into_iter()
+            .flat_map(|i| i.attributes.iter())
+            .collect()
+    }
+
+    /// Broadcasts of an interface including all inherited ones, base interface broadcasts first.
+    pub fn all_broadcasts<'a>(&'a self, interface: &'a FInterface) -> Vec<&'a FBroadcast> {
+        self.interface_hierarchy(interface)
+            .into_iter()
+            .flat_map(|i| i.broadcasts.iter())
+            .collect()
+    }
+
+    /// Interfaces listed in the `manages` clause that can be resolved.
+    pub fn managed_interfaces<'a>(&'a self, interface: &'a FInterface) -> Vec<&'a FInterface> {
+        interface
+            .managed_interfaces
+            .iter()
+            .filter_map(|name| self.find_interface(name))
+            .collect()
+    }
+
+    /// Inheritance chain of a struct starting with the root base struct
+    /// and ending with the struct itself.
+    /// Each base is resolved from the container of the struct extending it.
+    pub fn struct_hierarchy<'a>(&'a self, r#struct: &'a FStructType) -> Vec<&'a FStructType> {
+        hierarchy(r#struct, |s| {
+            let container =
+                self.type_container(|t| matches!(t, FType::Struct(t) if std::ptr::eq(t, s)));
+            match self.find_base(s.base.as_deref(), container) {
+                Some(FType::Struct(base)) => Some(base),
+                _ => None,
+            }
+        })
+    }
+
+    /// Fields of a struct including all inherited ones in declaration order, base fields first.
+    pub fn all_struct_fields<'a>(&'a self, r#struct: &'a FStructType) -> Vec<&'a FField> {
+        self.struct_hierarchy(r#struct)
+            .into_iter()
+            .flat_map(|s| s.elements.iter())
+            .collect()
+    }
+
+    /// All structs of the model directly or indirectly extending `base`,
+    /// `base` itself is not included.
+    /// Code generators use this to enumerate the variants of a `polymorphic` struct.
+    pub fn polymorphic_subtypes<'a>(&'a self, base: &'a FStructType) -> Vec<&'a FStructType> {
+        self.types()
+            .filter_map(|t| match t {
+                FType::Struct(s) => Some(s),
+                _ => None,
+            })
+            .filter(|s| !std::ptr::eq(*s, base))
+            .filter(|s| {
+                self.struct_hierarchy(s)
+                    .iter()
+                    .any(|ancestor| std::ptr::eq(*ancestor, base))
+            })
+            .collect()
+    }
+
+    /// Inheritance chain of a union starting with the root base union
+    /// and ending with the union itself.
+    pub fn union_hierarchy<'a>(&'a self, union: &'a FUnionType) -> Vec<&'a FUnionType> {
+        hierarchy(union, |u| {
+            let container =
+                self.type_container(|t| matches!(t, FType::Union(t) if std::ptr::eq(t, u)));
+            match self.find_base(u.base.as_deref(), container) {
+                Some(FType::Union(base)) => Some(base),
+                _ => None,
+            }
+        })
+    }
+
+    /// Fields of a union including all inherited ones in declaration order, base fields first.
+    pub fn all_union_fields<'a>(&'a self, union: &'a FUnionType) -> Vec<&'a FField> {
+        self.union_hierarchy(union)
+            .into_iter()
+            .flat_map(|u| u.elements.iter())
+            .collect()
+    }
+
+    /// Inheritance chain of an enumeration starting with the root base enumeration
+    /// and ending with the enumeration itself.
+    pub fn enumeration_hierarchy<'a>(
+        &'a self,
+        enumeration: &'a FEnumerationType,
+    ) -> Vec<&'a FEnumerationType> {
+        hierarchy(enumeration, |e| {
+            match self.find_base(e.base.as_deref(), self.enumeration_container(e)) {
+                Some(FType::Enumeration(base)) => Some(base),
+                _ => None,
+            }
+        })
+    }
+
+    /// Enumerators including all inherited ones in declaration order, base enumerators first.
+    pub fn all_enumerators<'a>(
+        &'a self,
+        enumeration: &'a FEnumerationType,
+    ) -> Vec<&'a FEnumerator> {
+        self.enumeration_hierarchy(enumeration)
+            .into_iter()
+            .flat_map(|e| e.enumerators.iter())
+            .collect()
+    }
+
+    /// Container defining the type `is` holds for.
+    pub(crate) fn type_container(&self, is: impl Fn(&FType) -> bool) -> Option<FContainer<'_>> {
+        self.containers().find(|c| c.types().iter().any(&is))
+    }
+
+    /// Container of an enumeration type or of the method declaring it as inline error enumeration.
+    pub(crate) fn enumeration_container(
+        &self,
+        enumeration: &FEnumerationType,
+    ) -> Option<FContainer<'_>> {
+        self.type_container(|t| matches!(t, FType::Enumeration(t) if std::ptr::eq(t, enumeration)))
+            .or_else(|| {
+                self.interfaces
+                    .iter()
+                    .find(|i| {
+                        i.methods.iter().any(|m| {
+                            m.errors
+                                .as_ref()
+                                .is_some_and(|e| std::ptr::eq(e, enumeration))
+                        })
+                    })
+                    .map(FContainer::Interface)
+            })
+    }
+
+    /// Container defining the constant, compared by identity.
+    pub(crate) fn constant_container(&self, constant: &FConstantDef) -> Option<FContainer<'_>> {
+        self.containers()
+            .find(|c| c.constants().iter().any(|d| std::ptr::eq(d, constant)))
+    }
+
+    /// Base types resolve like any other type, unresolvable or ambiguous bases end hierarchies.
+    fn find_base<'a>(
+        &'a self,
+        base: Option<&str>,
+        container: Option<FContainer<'a>>,
+    ) -> Option<&'a FType> {
+        base.and_then(|base| self.find_type(base, container).ok())
+    }
+
+    pub(crate) fn lookup_type<'a>(
+        &'a self,
+        name: &str,
+        container: Option<FContainer<'a>>,
+    ) -> Result<(FContainer<'a>, &'a FType), ResolveError> {
+        self.lookup(name, container, |c| c.types(), |t| t.name())
+    }
+
+    pub(crate) fn lookup_constant<'a>(
+        &'a self,
+        name: &str,
+        container: Option<FContainer<'a>>,
+    ) -> Result<(FContainer<'a>, &'a FConstantDef), ResolveError> {
+        self.lookup(name, container, |c| c.constants(), |c| &c.name)
+    }
+
+    /// Scoped lookup of [`FModel::find_type`] for any kind of element defined by containers.
+    fn lookup<'a, T>(
+        &'a self,
+        name: &str,
+        container: Option<FContainer<'a>>,
+        elements: impl Fn(FContainer<'a>) -> &'a [T],
+        element_name: impl Fn(&T) -> &str,
+    ) -> Result<(FContainer<'a>, &'a T), ResolveError> {
+        let (qualifier, simple_name) = match name.rsplit_once('.') {
+            Some((qualifier, simple_name)) => (Some(qualifier), simple_name),
+            None => (None, name),
+        };
+        let find = |c: FContainer<'a>| {
+            elements(c)
+                .iter()
+                .find(|e| element_name(e) == simple_name)
+                .map(|e| (c, e))
+        };
+        let unique = |candidates: Vec<(FContainer<'a>, &'a T)>| match candidates[..] {
+            [] => Err(ResolveError::Unknown(name.to_string())),
+            [found] => Ok(found),
+            _ => Err(ResolveError::Ambiguous(name.to_string())),
+        };
+
+        let container = match (qualifier, container) {
+            (Some(_), _) | (None, None) => {
+                return unique(
+                    self.containers()
+                        .filter(|c| self.qualifier_matches(qualifier, c.name()))
+                        .filter_map(find)
+                        .collect(),
+                )
+            }
+            (None, Some(container)) => container,
+        };
+
+        if let Some(found) = find(container) {
+            return Ok(found);
+        }
+        if let FContainer::Interface(interface) = container {
+            let bases = self
+                .interface_hierarchy(interface)
+                .into_iter()
+                .rev()
+                .skip(1);
+            if let Some(found) = bases.map(FContainer::Interface).find_map(find) {
+                return Ok(found);
+            }
+        }
+
+        // `import org.example.Common.* from ...` or `import org.example.Common.Type from ...`
+        let imported: Vec<_> = self
+            .imports
+            .iter()
+            .filter_map(|i| i.imported_namespace.as_deref())
+            .filter_map(|namespace| match namespace.strip_suffix(".*") {
+                Some(qualifier) => Some(qualifier),
+                None => namespace
+                    .rsplit_once('.')
+                    .filter(|(_, imported)| *imported == simple_name)
+                    .map(|(qualifier, _)| qualifier),
+            })
+            .collect();
+        unique(
+            self.containers()
+                .filter(|c| {
+                    c.name().is_none()
+                        || imported
+                            .iter()
+                            .any(|q| self.qualifier_matches(Some(q), c.name()))
+                })
+                .filter_map(find)
+                .collect(),
+        )
+    }
+
+    fn qualifier_matches(&self, qualifier: Option<&str>, container: Option<&str>) -> bool {
+        let qualifier = match qualifier {
+            Some(qualifier) => qualifier,
+            None => return true,
+        };
+
+        match container {
+            Some(container) => {
+                qualifier == container
+                    || qualifier
+                        .strip_prefix(self.name.as_str())
+                        .and_then(|q| q.strip_prefix('.'))
+                        == Some(container)
+            }
+            None => qualifier == self.name,
+        }
+    }
+}
+
+/// Follows `base` from `element` up to the root, returning the chain root first.
+/// Unresolvable bases end the chain, cyclic inheritance is visited only once.
+pub(crate) fn hierarchy<'a, T>(
+    element: &'a T,
+    base: impl Fn(&'a T) -> Option<&'a T>,
+) -> Vec<&'a T> {
+    let mut hierarchy = vec![element];
+    let mut current = element;
+    while let Some(next) = base(current) {
+        if hierarchy.iter().any(|e| std::ptr::eq(*e, next)) {
+            break;
+        }
+        hierarchy.push(next);
+        current = next;
+    }
+    hierarchy.reverse();
+    hierarchy
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_fidl, FBasicTypeId, FContainer, FType, ResolveError};
+
+    const HIERARCHY: &str = "package org.example
+
+interface Base {
+    attribute UInt32 id readonly
+    method reset {
+    }
+}
+
+interface Child extends org.example.Base manages Session, Missing {
+    attribute String label
+    method start {
+    }
+    broadcast started {
+        out {
+            UInt32 id
+        }
+    }
+}
+
+interface Session extends Cyclic {
+}
+
+interface Cyclic extends Session {
+}
+";
+
+    const MODEL: &str = "package org.example
+
+typeCollection {
+    typedef Timestamp is UInt64
+}
+
+typeCollection Common {
+    struct Position {
+        Double latitude
+        Double longitude
+    }
+}
+
+interface Navigation {
+    struct Position {
+        Float x
+    }
+
+    attribute Common.Position current readonly
+}
+";
+
+    #[test]
+    pub fn find_type_test() {
+        let model = parse_fidl(MODEL).unwrap();
+
+        let position = |t: Result<&FType, _>| match t {
+            Ok(FType::Struct(s)) => s.elements.len(),
+            _ => 0,
+        };
+
+        assert_eq!(position(model.find_type("Common.Position", None)), 2);
+        assert_eq!(
+            position(model.find_type("org.example.Common.Position", None)),
+            2
+        );
+        assert_eq!(position(model.find_type("Navigation.Position", None)), 1);
+        assert_eq!(
+            model
+                .find_type("org.example.Timestamp", None)
+                .unwrap()
+                .name(),
+            "Timestamp"
+        );
+        assert_eq!(
+            model.find_type("Timestamp", None).unwrap().name(),
+            "Timestamp"
+        );
+        assert!(model.find_type("other.Common.Position", None).is_err());
+        assert!(model.find_type("Missing", None).is_err());
+    }
+
+    #[test]
+    pub fn find_type_scoped_test() {
+        let def = "package org.example
+
+import org.example.Imported.* from \"imported.fidl\"
+
+typeCollection Common {
+    struct Position {
+        Double latitude
+        Double longitude
+    }
+
+    struct Track {
+        Position start
+    }
+
+    const UInt8 LIMIT = 1
+}
+
+typeCollection Imported {
+    struct Shared {
+        UInt8 a
+    }
+}
+
+typeCollection Other {
+    struct Shared {
+        UInt8 b
+    }
+}
+
+interface Navigation {
+    struct Position {
+        Float x
+    }
+
+    const UInt8 LIMIT = 2
+
+    attribute Position current
+}
+
+interface Route extends Navigation {
+    attribute Position next
+    attribute Shared shared
+}
+";
+
+        let model = parse_fidl(def).unwrap();
+        let common = FContainer::TypeCollection(&model.type_collections[0]);
+        let navigation = FContainer::Interface(&model.interfaces[0]);
+        let route = FContainer::Interface(&model.interfaces[1]);
+        let fields = |t: Result<&FType, _>| match t {
+            Ok(FType::Struct(s)) => s.elements.len(),
+            _ => 0,
+        };
+
+        // the referencing container shadows equally named types elsewhere
+        assert_eq!(fields(model.find_type("Position", Some(common))), 2);
+        assert_eq!(fields(model.find_type("Position", Some(navigation))), 1);
+        // base interfaces come next
+        assert_eq!(fields(model.find_type("Position", Some(route))), 1);
+        assert_eq!(fields(model.find_type("Common.Position", Some(route))), 2);
+        // imported namespaces last
+        assert!(matches!(
+            model.find_type("Shared", Some(route)),
+            Ok(FType::Struct(s)) if s.elements[0].name == "a"
+        ));
+        assert_eq!(
+            model.find_type("Shared", None),
+            Err(ResolveError::Ambiguous("Shared".to_string()))
+        );
+        assert_eq!(
+            model.find_type("Position", None),
+            Err(ResolveError::Ambiguous("Position".to_string()))
+        );
+        assert_eq!(
+            model.find_type("Track", Some(navigation)),
+            Err(ResolveError::Unknown("Track".to_string()))
+        );
+
+        let track = match model.find_type("Track", Some(common)) {
+            Ok(FType::Struct(track)) => track,
+            _ => panic!("struct Track not found"),
+        };
+        let start = model.resolve_type_ref(&track.elements[0].r#type, common);
+        assert_eq!(fields(start.map(Option::unwrap)), 2);
+
+        let limit = |container| model.find_constant("LIMIT", Some(container)).unwrap();
+        assert!(std::ptr::eq(
+            limit(common),
+            &model.type_collections[0].constants[0]
+        ));
+        assert!(std::ptr::eq(
+            limit(route),
+            &model.interfaces[0].constants[0]
+        ));
+    }
+
+    #[test]
+    pub fn resolve_type_ref_test() {
+        let model = parse_fidl(MODEL).unwrap();
+
+        let navigation = &model.interfaces[0];
+        let attribute = &navigation.attributes[0];
+        let resolved = model
+            .resolve_type_ref(&attribute.r#type, FContainer::Interface(navigation))
+            .unwrap();
+        assert!(matches!(resolved, Some(FType::Struct(s)) if s.elements.len() == 2));
+    }
+
+    #[test]
+    pub fn interface_inheritance_test() {
+        let model = parse_fidl(HIERARCHY).unwrap();
+        let child = model.find_interface("org.example.Child").unwrap();
+
+        assert_eq!(child.base.as_deref(), Some("org.example.Base"));
+        assert_eq!(child.managed_interfaces, ["Session", "Missing"]);
+
+        let methods: Vec<_> = model.all_methods(child).iter().map(|m| &m.name).collect();
+        assert_eq!(methods, ["reset", "start"]);
+        let attributes: Vec<_> = model
+            .all_attributes(child)
+            .iter()
+            .map(|a| &a.name)
+            .collect();
+        assert_eq!(attributes, ["id", "label"]);
+        assert_eq!(model.all_broadcasts(child).len(), 1);
+
+        let managed: Vec<_> = model
+            .managed_interfaces(child)
+            .iter()
+            .map(|i| &i.name)
+            .collect();
+        assert_eq!(managed, ["Session"]);
+
+        let session = model.find_interface("Session").unwrap();
+        let hierarchy: Vec<_> = model
+            .interface_hierarchy(session)
+            .iter()
+            .map(|i| &i.name)
+            .collect();
+        assert_eq!(hierarchy, ["Cyclic", "Session"]);
+    }
+
+    #[test]
+    pub fn type_inheritance_test() {
+        let def = "package org.example
+
+typeCollection Common {
+    struct A {
+        UInt8 a1
+        UInt8 a2
+    }
+
+    union U1 {
+        UInt8 number
+    }
+
+    enumeration E1 {
+        Off
+        On
+    }
+}
+
+typeCollection Derived {
+    struct B extends Common.A {
+        UInt8 b
+    }
+
+    struct C extends B {
+        UInt8 c
+    }
+
+    union U2 extends Common.U1 {
+        String text
+    }
+
+    enumeration E2 extends org.example.Common.E1 {
+        Standby
+    }
+}
+";
+
+        let model = parse_fidl(def).unwrap();
+
+        let c = match model.find_type("Derived.C", None) {
+            Ok(FType::Struct(c)) => c,
+            _ => panic!("struct C not found"),
+        };
+        assert_eq!(c.base.as_deref(), Some("B"));
+        let fields: Vec<_> = model
+            .all_struct_fields(c)
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(fields, ["a1", "a2", "b", "c"]);
+
+        let u2 = match model.find_type("U2", None) {
+            Ok(FType::Union(u2)) => u2,
+            _ => panic!("union U2 not found"),
+        };
+        let fields: Vec<_> = model
+            .all_union_fields(u2)
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(fields, ["number", "text"]);
+
+        let e2 = match model.find_type("E2", None) {
+            Ok(FType::Enumeration(e2)) => e2,
+            _ => panic!("enumeration E2 not found"),
+        };
+        let enumerators: Vec<_> = model
+            .all_enumerators(e2)
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(enumerators, ["Off", "On", "Standby"]);
+    }
+
+    #[test]
+    pub fn polymorphic_subtypes_test() {
+        let def = "package org.example
+
+typeCollection Shapes {
+    struct Shape polymorphic {
+        String name
+    }
+
+    struct Circle extends Shape {
+        Double radius
+    }
+
+    struct Point {
+        Double x
+        Double y
+    }
+}
+
+interface Drawing {
+    struct Shape polymorphic {
+        String label
+    }
+
+    struct Label extends Shape {
+        String text
+    }
+
+    struct Square extends Shapes.Shape {
+        Double side
+    }
+
+    struct ColoredCircle extends Shapes.Circle {
+        UInt32 color
+    }
+}
+";
+
+        let model = parse_fidl(def).unwrap();
+        let shape = match model.find_type("Shapes.Shape", None) {
+            Ok(FType::Struct(shape)) => shape,
+            _ => panic!("struct Shape not found"),
+        };
+        assert_eq!(shape.polymorphic, Some(true));
+
+        let mut subtypes: Vec<_> = model
+            .polymorphic_subtypes(shape)
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        subtypes.sort_unstable();
+        assert_eq!(subtypes, ["Circle", "ColoredCircle", "Square"]);
+
+        // `Label` extends the equally named `Shape` of its own interface
+        let drawing_shape = match model.find_type("Drawing.Shape", None) {
+            Ok(FType::Struct(shape)) => shape,
+            _ => panic!("struct Drawing.Shape not found"),
+        };
+        let subtypes: Vec<_> = model
+            .polymorphic_subtypes(drawing_shape)
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(subtypes, ["Label"]);
+    }
+
+    #[test]
+    pub fn element_type_test() {
+        let def = "package org.example
+
+typeCollection Common {
+    array Payload of UInt8
+    typedef Frame is Payload
+    typedef Bytes is UInt16[]
+
+    struct Message {
+        UInt32[] ids
+        Frame frame
+        Bytes bytes
+        UInt8 single
+    }
+
+    map Lookup { String to Payload }
+}
+";
+
+        let model = parse_fidl(def).unwrap();
+        let message = match model.find_type("Common.Message", None) {
+            Ok(FType::Struct(message)) => message,
+            _ => panic!("struct Message not found"),
+        };
+
+        let common = FContainer::TypeCollection(&model.type_collections[0]);
+        let element_types: Vec<_> = message
+            .elements
+            .iter()
+            .map(|field| {
+                model
+                    .element_type(field, common)
+                    .and_then(|(t, _)| t.predefined)
+            })
+            .collect();
+        assert_eq!(
+            element_types,
+            [
+                Some(FBasicTypeId::UInt32),
+                Some(FBasicTypeId::UInt8),
+                Some(FBasicTypeId::UInt16),
+                None
+            ]
+        );
+
+        let lookup = match model.find_type("Lookup", None) {
+            Ok(FType::Map(lookup)) => lookup,
+            _ => panic!("map Lookup not found"),
+        };
+        assert!(model
+            .array_element_type(&lookup.key_type, false, common)
+            .is_none());
+        assert_eq!(
+            model
+                .array_element_type(&lookup.value_type, false, common)
+                .and_then(|(t, _)| t.predefined),
+            Some(FBasicTypeId::UInt8)
+        );
+    }
+}